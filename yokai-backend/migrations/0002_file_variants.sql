@@ -0,0 +1,6 @@
+create table file_variants(
+  variant_id text not null primary key,
+  file_id text not null,
+  variant text not null,
+  file_path text not null
+);
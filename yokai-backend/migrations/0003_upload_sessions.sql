@@ -0,0 +1,11 @@
+create table upload_sessions(
+  session_id text not null primary key,
+  owner text not null,
+  file_name text not null,
+  file_path text not null,
+  total_size bigint not null,
+  received bigint not null,
+  checksum text not null,
+  created text not null,
+  updated text not null
+);
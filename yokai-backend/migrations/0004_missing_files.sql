@@ -0,0 +1 @@
+alter table user_files add column missing boolean not null default false;
@@ -0,0 +1 @@
+alter table user_files add column scan_verdict text;
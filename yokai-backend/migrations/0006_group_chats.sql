@@ -0,0 +1,21 @@
+alter table chats add column is_group boolean not null default false;
+alter table chats add column title text;
+alter table chats alter column receiver drop not null;
+alter table messages alter column receiver drop not null;
+
+create table chat_members(
+  chat_id text not null,
+  username text not null,
+  member_role text not null,
+  joined text not null,
+  pending boolean not null default false,
+  invited_by text references users(username),
+  primary key (chat_id, username)
+);
+
+create table message_keys(
+  msg_id text not null,
+  recipient text not null,
+  content text not null,
+  primary key (msg_id, recipient)
+);
@@ -0,0 +1,10 @@
+alter table messages add column edited text;
+alter table messages add column deleted boolean not null default false;
+
+create table message_edits(
+  edit_id text not null primary key,
+  msg_id text not null,
+  recipient text not null,
+  content text not null,
+  edited text not null
+);
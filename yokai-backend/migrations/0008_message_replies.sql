@@ -0,0 +1,2 @@
+alter table messages add column reply_to text;
+alter table messages add column thread_root text;
@@ -0,0 +1,7 @@
+create table message_reactions(
+  msg_id text not null,
+  username text not null,
+  emoji text not null,
+  reacted text not null,
+  primary key (msg_id, username, emoji)
+);
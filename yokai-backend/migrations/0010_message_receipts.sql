@@ -0,0 +1,4 @@
+alter table message_keys add column delivered_at text;
+alter table message_keys add column read_at text;
+alter table users add column read_receipts boolean not null default true;
+create index messages_chat_id on messages (chat_id);
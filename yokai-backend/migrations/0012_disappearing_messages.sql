@@ -0,0 +1,3 @@
+alter table chats add column message_ttl bigint;
+alter table messages add column expires_at text;
+alter table messages add column is_system boolean not null default false;
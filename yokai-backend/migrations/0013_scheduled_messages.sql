@@ -0,0 +1,13 @@
+create table scheduled_messages(
+  schedule_id text not null primary key,
+  chat_id text not null,
+  sender text not null,
+  content text not null,
+  attachment text,
+  reply_to text,
+  send_at text not null,
+  created text not null,
+  attempts integer not null default 0,
+  last_error text,
+  failed boolean not null default false
+);
@@ -0,0 +1,13 @@
+create table pinned_messages(
+  chat_id text not null,
+  msg_id text not null,
+  pinned_by text not null,
+  pinned text not null,
+  primary key (chat_id, msg_id)
+);
+create table starred_messages(
+  username text not null,
+  msg_id text not null,
+  starred text not null,
+  primary key (username, msg_id)
+);
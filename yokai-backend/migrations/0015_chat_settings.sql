@@ -0,0 +1,9 @@
+create table chat_settings(
+  chat_id text not null,
+  username text not null,
+  archived boolean not null default false,
+  muted_until text,
+  pinned boolean not null default false,
+  nickname text,
+  primary key (chat_id, username)
+);
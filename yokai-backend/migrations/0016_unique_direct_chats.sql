@@ -0,0 +1,41 @@
+create temporary table chat_merges as
+select chat_id, first_value(chat_id) over (
+  partition by least(sender, receiver), greatest(sender, receiver)
+  order by started::timestamptz, chat_id
+) as keep_id
+from chats
+where is_group = false;
+
+delete from chat_merges where chat_id = keep_id;
+
+update messages set chat_id = chat_merges.keep_id from chat_merges where messages.chat_id = chat_merges.chat_id;
+update scheduled_messages set chat_id = chat_merges.keep_id from chat_merges where scheduled_messages.chat_id = chat_merges.chat_id;
+update pinned_messages set chat_id = chat_merges.keep_id from chat_merges where pinned_messages.chat_id = chat_merges.chat_id;
+
+delete from chat_settings where ctid in (
+  select ctid from (
+    select chat_settings.ctid, row_number() over (
+      partition by coalesce(chat_merges.keep_id, chat_settings.chat_id), chat_settings.username
+      order by chat_merges.chat_id is not null, chat_settings.chat_id
+    ) as rank
+    from chat_settings left join chat_merges on chat_merges.chat_id = chat_settings.chat_id
+  ) ranked where rank > 1
+);
+update chat_settings set chat_id = chat_merges.keep_id from chat_merges where chat_settings.chat_id = chat_merges.chat_id;
+
+delete from chat_members where ctid in (
+  select ctid from (
+    select chat_members.ctid, row_number() over (
+      partition by coalesce(chat_merges.keep_id, chat_members.chat_id), chat_members.username
+      order by chat_merges.chat_id is not null, chat_members.chat_id
+    ) as rank
+    from chat_members left join chat_merges on chat_merges.chat_id = chat_members.chat_id
+  ) ranked where rank > 1
+);
+update chat_members set chat_id = chat_merges.keep_id from chat_merges where chat_members.chat_id = chat_merges.chat_id;
+
+delete from chats using chat_merges where chats.chat_id = chat_merges.chat_id;
+
+drop table chat_merges;
+
+create unique index chats_direct_pair on chats (least(sender, receiver), greatest(sender, receiver)) where is_group = false;
@@ -0,0 +1,6 @@
+create table user_blocks(
+  blocker text not null,
+  blocked text not null,
+  created text not null,
+  primary key (blocker, blocked)
+);
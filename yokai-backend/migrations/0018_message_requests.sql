@@ -0,0 +1,2 @@
+alter table users add column require_approval boolean not null default false;
+alter table chats add column pending boolean not null default false;
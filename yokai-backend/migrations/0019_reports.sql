@@ -0,0 +1,13 @@
+create table reports(
+  report_id text not null primary key,
+  reporter text not null,
+  reported_user text not null,
+  msg_id text,
+  reason text not null,
+  disclosed text,
+  status text not null default 'open',
+  created text not null,
+  resolved text,
+  resolved_by text
+);
+create unique index reports_open_pair on reports (reporter, reported_user, coalesce(msg_id, '')) where status = 'open';
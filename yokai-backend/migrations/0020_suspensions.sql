@@ -0,0 +1,3 @@
+alter table users add column suspended_at text;
+alter table users add column suspended_until text;
+alter table users add column suspension_reason text;
@@ -0,0 +1,3 @@
+alter table users add column user_role text not null default 'user';
+update users set user_role = 'admin' where is_admin = true;
+alter table users drop column is_admin;
@@ -0,0 +1,21 @@
+create table audit_log(
+  seq bigserial primary key,
+  actor text not null,
+  action text not null,
+  target text,
+  details text,
+  ip text,
+  created text not null
+);
+create function audit_log_append_only() returns trigger as $$
+begin
+  raise exception 'audit_log is append-only';
+end;
+$$ language plpgsql;
+create trigger audit_log_no_change
+  before update or delete on audit_log
+  for each row execute function audit_log_append_only();
+create trigger audit_log_no_truncate
+  before truncate on audit_log
+  for each statement execute function audit_log_append_only();
+revoke update, delete, truncate on audit_log from public, current_user;
@@ -0,0 +1,2 @@
+alter table users add column last_login text;
+alter table user_files add column file_size bigint not null default 0;
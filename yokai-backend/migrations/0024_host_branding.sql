@@ -0,0 +1,7 @@
+alter table host_info add column instance_name text;
+alter table host_info add column description text;
+alter table host_info add column logo_file_id text;
+alter table host_info add column favicon_file_id text;
+alter table host_info add column terms text;
+alter table host_info add column privacy_policy text;
+alter table host_info add column contact_address text;
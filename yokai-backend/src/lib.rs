@@ -9,10 +9,15 @@ pub mod modules;
 pub use modules::db::*;
 pub use modules::api::*;
 pub use modules::err::*;
+pub use modules::jobs::*;
 pub use modules::utils::*;
 pub use modules::units::*;
+pub use modules::events::*;
 pub use modules::config::*;
+pub use modules::images::*;
 pub use modules::models::*;
 pub use modules::runner::*;
+pub use modules::scanner::*;
+pub use modules::presence::*;
 pub use modules::payloads::*;
 pub use modules::responses::*;
@@ -3,25 +3,92 @@ Yokai by Alyx Shang.
 Licensed under the FSL v1.
 */
 
+use sqlx::Pool;
+use chrono::Utc;
+use std::fs::File;
+use std::io::Seek;
+use actix_web::get;
+use std::io::Write;
+use std::fs::rename;
 use actix_web::post;
+use actix_web::patch;
+use std::io::SeekFrom;
+use sqlx::Transaction;
 use std::path::PathBuf;
-use std::fs::create_dir;
+use super::models::Chat;
 use super::models::User;
+use super::db::mark_read;
+use std::fs::remove_file;
+use std::fs::OpenOptions;
+use actix_web::web::Path;
 use actix_web::web::Data;
 use super::err::YokaiErr;
 use actix_web::web::Json;
+use super::db::open_chat;
+use std::slice::from_ref;
+use actix_web::rt::spawn;
+use actix_web::web::Query;
+use actix_web::web::Bytes;
+use actix_web::web::block;
+use super::db::pfp_in_use;
 use super::units::AppData;
 use super::utils::rfc2282;
-use std::env::current_dir;
+use super::db::block_user;
+use super::models::Report;
+use super::db::get_report;
+use super::models::Message;
+use actix_web::HttpRequest;
 use super::db::user_exists;
 use super::db::create_chat;
 use actix_files::NamedFile;
 use super::db::create_user;
+use super::db::pin_message;
+use super::db::get_reports;
+use super::db::count_users;
+use super::db::count_chats;
+use super::roles::outranks;
+use super::db::count_files;
+use tokio_stream::StreamExt;
+use super::db::add_reaction;
+use super::db::edit_message;
+use super::db::create_group;
+use super::utils::hash_file;
 use super::models::UserFile;
 use super::db::delete_token;
 use actix_web::HttpResponse;
-use super::db::file_on_file;
+use super::db::star_message;
+use super::db::unblock_user;
+use super::db::suspend_user;
+use super::roles::ROLE_USER;
+use super::db::record_audit;
+use super::db::search_users;
+use super::db::storage_used;
+use super::events::EventHub;
+use super::db::count_reports;
+use super::utils::hash_bytes;
+use super::units::OWNER_ROLE;
+use super::units::ADMIN_ROLE;
+use super::utils::get_header;
 use super::db::edit_user_pfp;
+use super::db::unpin_message;
+use super::db::get_chat_pins;
+use super::models::UserBlock;
+use super::db::create_report;
+use super::db::set_user_role;
+use super::roles::Permission;
+use super::utils::request_ip;
+use super::db::get_host_info;
+use sqlx::postgres::Postgres;
+use super::db::purge_account;
+use super::db::unmuted_users;
+use super::db::mark_delivered;
+use super::units::MEMBER_ROLE;
+use super::db::get_chat_by_id;
+use super::db::get_user_chats;
+use super::db::is_chat_member;
+use super::models::ChatMember;
+use super::utils::storage_dir;
+use super::images::avatar_url;
 use super::db::get_user_by_id;
 use super::models::InviteCode;
 use super::db::delete_account;
@@ -29,53 +96,237 @@ use super::utils::hash_string;
 use super::db::get_file_by_id;
 use super::db::get_user_files;
 use super::db::create_message;
+use super::db::set_chat_timer;
+use tokio::sync::mpsc::Sender;
+use super::db::get_audit_page;
+use super::db::remove_account;
+use super::scanner::scan_path;
+use super::db::count_messages;
+use super::models::AuditEntry;
+use super::units::ACTION_KICK;
+use super::db::unsuspend_user;
+use super::db::report_message;
+use super::db::resolve_report;
+use std::collections::HashMap;
+use super::db::set_chat_muted;
+use super::db::unstar_message;
+use super::db::get_user_stars;
+use tokio::sync::mpsc::channel;
+use super::units::BrandingForm;
+use super::utils::is_suspended;
+use super::db::get_user_blocks;
+use super::models::ChatSetting;
+use super::db::get_member_chat;
+use super::db::set_chat_pinned;
+use super::db::remove_reaction;
+use super::db::render_messages;
+use super::models::MessageEdit;
+use super::db::get_chat_member;
+use super::db::add_chat_member;
+use super::db::set_member_role;
+use super::models::FileVariant;
 use super::db::get_user_tokens;
+use super::units::BRANDING_LOGO;
+use super::utils::seconds_since;
+use super::db::get_chat_members;
+use super::utils::sign_download;
+use super::db::file_shared_with;
+use super::db::get_file_variant;
 use super::models::UserAPIToken;
 use super::db::create_api_token;
 use super::db::create_user_file;
 use super::db::delete_user_file;
+use super::units::MAX_PAGE_SIZE;
+use super::images::branding_path;
+use super::db::awaiting_approval;
+use super::db::get_audit_entries;
+use super::roles::has_permission;
+use super::units::ACTION_SUSPEND;
+use super::db::get_chat_requests;
+use super::db::set_chat_archived;
+use super::db::set_chat_nickname;
+use super::models::PinnedMessage;
+use super::responses::PinSummary;
+use super::payloads::UserPayload;
+use super::payloads::ReadPayload;
+use super::db::get_message_by_id;
+use super::db::tombstone_message;
+use super::db::get_message_edits;
+use super::payloads::ChatPayload;
+use super::db::get_chat_messages;
+use super::db::chat_participants;
+use super::models::UploadSession;
 use super::payloads::EditPayload;
 use super::db::get_user_by_token;
-use super::db::edit_host_primary;
 use super::db::edit_user_primary;
 use super::db::get_user_contacts;
 use super::units::FileUploadForm;
 use super::payloads::FilePayload;
+use super::events::MESSAGE_EVENT;
+use super::utils::generate_secret;
+use super::db::edit_host_branding;
+use super::units::AUDIT_HOST_EDIT;
+use super::units::AUDIT_USER_KICK;
+use super::units::AUDIT_USER_ROLE;
+use super::db::edit_user_approval;
+use super::responses::ChatRequest;
+use super::models::StarredMessage;
+use super::responses::StarSummary;
+use super::presence::TYPING_EVENT;
+use super::responses::TypingEvent;
+use super::events::REACTION_EVENT;
+use super::responses::ChatSummary;
+use super::responses::GroupMember;
+use super::responses::ChatMessage;
+use super::db::remove_chat_member;
+use super::utils::verify_download;
+use super::db::get_upload_session;
+use super::images::AVATAR_VARIANT;
 use super::utils::decrypt_message;
-use super::utils::encrypt_message;
 use super::responses::UserContact;
 use super::db::delete_invite_code;
 use super::db::get_token_by_token;
 use super::payloads::LoginPayload;
 use super::db::edit_user_tertiary;
-use super::db::edit_host_tertiary;
 use super::db::create_invite_code;
 use super::db::edit_user_password;
+use super::responses::MessageEvent;
+use super::db::remove_stored_files;
+use super::images::render_branding;
+use super::db::active_participants;
+use super::units::BRANDING_FAVICON;
+use super::models::HostInformation;
+use super::db::count_user_messages;
+use super::db::get_suspended_users;
+use super::db::count_audit_entries;
+use super::db::accept_chat_request;
+use super::responses::BlockSummary;
+use super::responses::PinsResponse;
+use super::presence::ONLINE_STATUS;
+use super::db::edit_user_last_seen;
+use super::payloads::TypingPayload;
+use super::responses::UserPresence;
+use super::payloads::TogglePayload;
+use super::db::get_thread_messages;
+use super::db::is_chat_participant;
+use super::scanner::is_quarantined;
+use super::scanner::scan_user_file;
+use super::payloads::DownloadQuery;
 use super::responses::ListResponse;
 use super::payloads::LogoutPayload;
 use super::db::edit_user_secondary;
-use super::db::edit_host_secondary;
+use super::db::lock_upload_session;
+use super::units::MAX_POLICY_LENGTH;
+use super::units::REGISTRATION_MODE;
+use super::responses::DirectoryUser;
+use super::units::DEFAULT_PAGE_SIZE;
+use super::responses::ReportSummary;
+use super::db::decline_chat_request;
+use super::responses::StarsResponse;
+use super::models::ScheduledMessage;
+use super::db::message_participants;
+use super::responses::ReactionEvent;
+use super::payloads::MessagePayload;
+use super::responses::ChatsResponse;
+use super::units::DOWNLOAD_LINK_TTL;
 use super::responses::TokenResponse;
+use super::units::MAX_MESSAGE_BYTES;
+use super::utils::edit_optional_text;
+use super::units::UPLOAD_CHUNK_LIMIT;
+use super::units::ENCRYPTION_SCHEMES;
+use super::units::AUDIT_USER_SUSPEND;
+use super::payloads::UserRolePayload;
+use super::responses::BlocksResponse;
+use super::payloads::ChatListPayload;
+use super::payloads::ChatMutePayload;
+use super::payloads::PresencePayload;
+use super::responses::TypersResponse;
+use super::payloads::ReactionPayload;
+use super::responses::ChatIdResponse;
+use super::responses::FileIdResponse;
+use super::db::create_upload_session;
+use super::db::delete_upload_session;
 use super::payloads::KickUserPayload;
 use super::db::edit_user_description;
 use super::responses::StatusResponse;
+use super::units::AUDIT_INVITE_CREATE;
+use super::responses::ReportsResponse;
+use super::db::get_user_chat_settings;
+use super::payloads::ScheduledPayload;
+use super::payloads::ChatTimerPayload;
+use super::presence::can_see_presence;
+use super::responses::MessageRevision;
+use super::payloads::GroupRolePayload;
+use super::db::advance_upload_session;
 use super::payloads::TokenOnlyPayload;
 use super::db::edit_user_display_name;
+use super::db::complete_upload_session;
+use super::units::AUDIT_USER_UNSUSPEND;
+use super::responses::AuditLogResponse;
+use super::payloads::AuditQueryPayload;
+use super::responses::ReportIdResponse;
+use super::payloads::ReportUserPayload;
+use super::payloads::ChatTogglePayload;
+use super::responses::ScheduledSummary;
+use super::responses::PresenceResponse;
+use super::db::edit_user_read_receipts;
+use super::responses::FileLinkResponse;
 use super::payloads::DecryptionPayload;
 use super::payloads::ChatCreatePayload;
 use super::payloads::UserCreatePayload;
+use super::utils::check_contact_address;
+use super::responses::UserStatsResponse;
+use super::responses::AuditEntrySummary;
+use super::payloads::SuspendUserPayload;
+use super::payloads::ReportQueuePayload;
+use super::payloads::ChatDeclinePayload;
+use super::db::create_scheduled_message;
+use super::db::cancel_scheduled_message;
+use super::payloads::EditMessagePayload;
+use super::payloads::GroupMemberPayload;
+use super::payloads::GroupCreatePayload;
+use super::jobs::collect_orphaned_files;
 use super::payloads::SendMessagePayload;
+use super::units::MAX_DESCRIPTION_LENGTH;
+use super::payloads::ChatNicknamePayload;
+use tokio::sync::mpsc::UnboundedReceiver;
+use actix_web::http::header::HeaderValue;
+use super::payloads::UploadCreatePayload;
+use super::images::create_image_variants;
 use actix_multipart::form::MultipartForm;
 use super::responses::UserCreateResponse;
 use super::payloads::InviteCreatePayload;
 use super::responses::DecryptionResponse;
+use super::payloads::UserDirectoryPayload;
+use super::payloads::ReportMessagePayload;
+use super::payloads::ResolveReportPayload;
+use super::responses::ScheduledIdResponse;
+use super::payloads::UploadSessionPayload;
 use super::payloads::ChangePassworPayload;
+use tokio_stream::wrappers::ReceiverStream;
+use super::units::MAX_INSTANCE_NAME_LENGTH;
+use super::responses::InstanceInfoResponse;
+use super::responses::ChatRequestsResponse;
+use super::db::get_user_scheduled_messages;
+use super::responses::ChatMessagesResponse;
+use super::responses::GroupMembersResponse;
+use actix_web::http::header::CACHE_CONTROL;
 use super::responses::UserContactsResponse;
 use super::responses::InviteCreateResponse;
+use super::responses::UserDirectoryResponse;
+use super::responses::InstanceStatsResponse;
+use super::responses::StorageReportResponse;
+use super::responses::UploadSessionResponse;
+use super::units::MAX_CONTACT_ADDRESS_LENGTH;
+use super::responses::MessageHistoryResponse;
+use actix_multipart::form::tempfile::TempFile;
+use super::responses::ScheduledMessagesResponse;
+use tokio_stream::wrappers::UnboundedReceiverStream;
 
 #[post("/invite/create")]
 pub async fn invite_create_service(
     payload: Json<InviteCreatePayload>,
+    req: HttpRequest,
     data: Data<AppData>
 ) -> Result<HttpResponse, YokaiErr>{
     let user: User = match get_user_by_token(
@@ -87,16 +338,41 @@ pub async fn invite_create_service(
             YokaiErr::new(&e.to_string())
         )
     };
-    if user.is_admin{
+    if has_permission(&user, &Permission::ManageInvites){
+        let mut tx: Transaction<'_, Postgres> = match data.pool.begin().await {
+            Ok(tx) => tx,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
         let code: InviteCode = match create_invite_code(
             &payload.code,
-            &data.pool
+            &mut tx
         ).await {
             Ok(code) => code,
             Err(e) => return Err::<HttpResponse, YokaiErr>(
                 YokaiErr::new(&e.to_string())
             )
         };
+        let _: () = match record_audit(
+            &user.username,
+            AUDIT_INVITE_CREATE,
+            &Some(code.invite_code.clone()),
+            &None,
+            &request_ip(&req, &data.trusted_proxies),
+            &mut tx
+        ).await {
+            Ok(_f) => {},
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let _: () = match tx.commit().await {
+            Ok(_f) => {},
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
         let result: InviteCreateResponse = InviteCreateResponse {
             code: code.invite_code
         };
@@ -104,7 +380,7 @@ pub async fn invite_create_service(
     }
     else {
         Err::<HttpResponse, YokaiErr>(
-            YokaiErr::new("User is not an administrator.")
+            YokaiErr::new("Requesting user lacks permission for this action.")
         )
     }
 }
@@ -125,7 +401,7 @@ pub async fn user_create_service(
         let user: User = match create_user(
             &payload.username,
             &payload.password,
-            &false,
+            ROLE_USER,
             &payload.description,
             &payload.display_name,
             &payload.primary_color,
@@ -293,6 +569,90 @@ pub async fn edit_bio_service(
 
 }
 
+#[post("/user/edit/lastseen")]
+pub async fn edit_last_seen_service(
+    payload: Json<EditPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let edit: bool = match edit_user_last_seen(
+        &user.username,
+        &payload.new_value,
+        &data.pool
+    ).await {
+        Ok(_f) => true,
+        Err(_e) => false
+    };
+    let result: StatusResponse = StatusResponse{
+        status: edit
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/user/edit/receipts")]
+pub async fn edit_read_receipts_service(
+    payload: Json<TogglePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let edit: bool = match edit_user_read_receipts(
+        &user.username,
+        &payload.enabled,
+        &data.pool
+    ).await {
+        Ok(_f) => true,
+        Err(_e) => false
+    };
+    let result: StatusResponse = StatusResponse{
+        status: edit
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/user/edit/approval")]
+pub async fn edit_approval_service(
+    payload: Json<TogglePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let edit: bool = match edit_user_approval(
+        &user.username,
+        &payload.enabled,
+        &data.pool
+    ).await {
+        Ok(_f) => true,
+        Err(_e) => false
+    };
+    let result: StatusResponse = StatusResponse{
+        status: edit
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
 #[post("/user/edit/primary")]
 pub async fn edit_user_primary_service(
     payload: Json<EditPayload>,
@@ -391,11 +751,26 @@ pub async fn edit_pfp_service(
             YokaiErr::new(&e.to_string())
         )
     };
-    let f_exists: bool = file_on_file(
-        &payload.new_value, 
+    let file: UserFile = match get_file_by_id(
+        &payload.new_value,
         &data.pool
-    ).await;
-    if f_exists{
+    ).await {
+        Ok(file) => file,
+        Err(_e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("The file supplied does not exist.")
+        )
+    };
+    if file.file_owner != user.username {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("File ownership could not be verified.")
+        );
+    }
+    let is_image: bool = get_file_variant(
+        &file.file_id,
+        AVATAR_VARIANT,
+        &data.pool
+    ).await.is_ok();
+    if is_image{
         let edit: bool = match edit_user_pfp(
             &user.username,
             &payload.new_value,
@@ -411,52 +786,85 @@ pub async fn edit_pfp_service(
     }
     else {
         Err::<HttpResponse, YokaiErr>(
-            YokaiErr::new("The file supplied does not exist.")
+            YokaiErr::new("The file supplied is not a valid image.")
         )
     }
 }
 
-#[post("/host/edit/primary")]
-pub async fn edit_host_primary_service(
-    payload: Json<EditPayload>,
-    data: Data<AppData>
-) -> Result<HttpResponse, YokaiErr>{
-    let user: User = match get_user_by_token(
-       &payload.api_token,
-       &data.pool
+async fn store_branding_asset(
+    asset: TempFile,
+    asset_name: &str,
+    data: &Data<AppData>
+) -> Result<String, YokaiErr>{
+    let salt: String = match generate_secret(){
+        Ok(salt) => salt,
+        Err(e) => return Err::<String, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let file_id: String = hash_string(
+        &format!(
+            "{}{}{}",
+            asset_name,
+            &rfc2282(),
+            &salt
+        )
+    );
+    let dest: PathBuf = match branding_path(&file_id){
+        Ok(dest) => dest,
+        Err(e) => return Err::<String, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if asset.size as i64 > data.max_upload_size {
+        return Err::<String, YokaiErr>(
+            YokaiErr::new("Upload size is not within the allowed limits.")
+        );
+    }
+    let upload: PathBuf = asset.file.path().to_path_buf();
+    let _: () = match scan_path(
+        upload.as_path(),
+        &data.clamav_socket
     ).await {
-        Ok(user) => user,
-        Err(e) => return Err::<HttpResponse, YokaiErr>(
+        Ok(_f) => {},
+        Err(e) => return Err::<String, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    if user.is_admin{
-        let edit: bool = match edit_host_primary(
-            &payload.new_value,
-            &data.pool
-        ).await {
-            Ok(_f) => true,
-            Err(_e) => false
-        };
-        let result: StatusResponse = StatusResponse{
-            status: edit
-        };
-        Ok(HttpResponse::Ok().json(result))
+    let render_dest: PathBuf = dest.clone();
+    let rendered: bool = matches!(
+        block(move || render_branding(upload.as_path(), render_dest.as_path())).await,
+        Ok(Ok(_))
+    );
+    if rendered {
+        Ok(file_id)
     }
     else {
-        Err::<HttpResponse, YokaiErr>(
-            YokaiErr::new("Requesting user is not an administrator.")
+        let _ = remove_file(dest.as_path());
+        Err::<String, YokaiErr>(
+            YokaiErr::new("The file supplied is not a valid image.")
         )
     }
 }
 
-#[post("/host/edit/secondary")]
-pub async fn edit_host_secondary_service(
-    payload: Json<EditPayload>,
+fn discard_branding_assets(
+    file_ids: &[Option<String>]
+) {
+    for file_id in file_ids.iter().flatten() {
+        if let Ok(path) = branding_path(file_id){
+            let _ = remove_file(path.as_path());
+        }
+    }
+}
+
+#[patch("/host/branding")]
+pub async fn edit_host_branding_service(
+    MultipartForm(form): MultipartForm<BrandingForm>,
+    req: HttpRequest,
     data: Data<AppData>
 ) -> Result<HttpResponse, YokaiErr>{
     let user: User = match get_user_by_token(
-       &payload.api_token,
+       &form.json.api_token,
        &data.pool
     ).await {
         Ok(user) => user,
@@ -464,63 +872,215 @@ pub async fn edit_host_secondary_service(
             YokaiErr::new(&e.to_string())
         )
     };
-    if user.is_admin{
-        let edit: bool = match edit_host_secondary(
-            &payload.new_value,
-            &data.pool
-        ).await {
-            Ok(_f) => true,
-            Err(_e) => false
-        };
-        let result: StatusResponse = StatusResponse{
-            status: edit
-        };
-        Ok(HttpResponse::Ok().json(result))
+    if !has_permission(&user, &Permission::ManageHost){
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Requesting user lacks permission for this action.")
+        );
     }
-    else {
-        Err::<HttpResponse, YokaiErr>(
-            YokaiErr::new("Requesting user is not an administrator.")
+    let info: HostInformation = match get_host_info(
+        &data.pool
+    ).await {
+        Ok(info) => info,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
         )
+    };
+    if let Some(address) = &form.json.contact_address &&
+       !address.trim().is_empty() &&
+       !check_contact_address(address.trim())
+    {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Invalid contact address received.")
+        );
     }
-}
-
-#[post("/host/edit/tertiary")]
-pub async fn edit_host_tertiary_service(
-    payload: Json<EditPayload>,
-    data: Data<AppData>
-) -> Result<HttpResponse, YokaiErr>{
-    let user: User = match get_user_by_token(
-       &payload.api_token,
-       &data.pool
-    ).await {
-        Ok(user) => user,
+    let mut branding: HostInformation = info.clone();
+    branding.instance_name = match edit_optional_text(
+        &info.instance_name,
+        &form.json.instance_name,
+        MAX_INSTANCE_NAME_LENGTH
+    ){
+        Ok(instance_name) => instance_name,
         Err(e) => return Err::<HttpResponse, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    if user.is_admin{
-        let edit: bool = match edit_host_tertiary(
-            &payload.new_value,
-            &data.pool
+    branding.description = match edit_optional_text(
+        &info.description,
+        &form.json.description,
+        MAX_DESCRIPTION_LENGTH
+    ){
+        Ok(description) => description,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    branding.terms = match edit_optional_text(
+        &info.terms,
+        &form.json.terms,
+        MAX_POLICY_LENGTH
+    ){
+        Ok(terms) => terms,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    branding.privacy_policy = match edit_optional_text(
+        &info.privacy_policy,
+        &form.json.privacy_policy,
+        MAX_POLICY_LENGTH
+    ){
+        Ok(privacy_policy) => privacy_policy,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    branding.contact_address = match edit_optional_text(
+        &info.contact_address,
+        &form.json.contact_address,
+        MAX_CONTACT_ADDRESS_LENGTH
+    ){
+        Ok(contact_address) => contact_address,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if let Some(primary_color) = &form.json.primary_color {
+        branding.primary_color = primary_color.clone();
+    }
+    if let Some(secondary_color) = &form.json.secondary_color {
+        branding.secondary_color = secondary_color.clone();
+    }
+    if let Some(tertiary_color) = &form.json.tertiary_color {
+        branding.tertiary_color = tertiary_color.clone();
+    }
+    if form.json.remove_logo == Some(true) {
+        branding.logo_file_id = None;
+    }
+    if form.json.remove_favicon == Some(true) {
+        branding.favicon_file_id = None;
+    }
+    let mut changes: Vec<String> = Vec::new();
+    for (field, changed) in [
+        ("instance_name", branding.instance_name != info.instance_name),
+        ("description", branding.description != info.description),
+        ("terms", branding.terms != info.terms),
+        ("privacy_policy", branding.privacy_policy != info.privacy_policy),
+        ("contact_address", branding.contact_address != info.contact_address)
+    ] {
+        if changed {
+            changes.push(field.to_string());
+        }
+    }
+    for (field, old_color, new_color) in [
+        ("primary", &info.primary_color, &branding.primary_color),
+        ("secondary", &info.secondary_color, &branding.secondary_color),
+        ("tertiary", &info.tertiary_color, &branding.tertiary_color)
+    ] {
+        if old_color != new_color {
+            changes.push(format!("{}: {}", field, new_color));
+        }
+    }
+    let mut stored: Vec<Option<String>> = Vec::new();
+    if let Some(logo) = form.logo {
+        branding.logo_file_id = match store_branding_asset(
+            logo,
+            BRANDING_LOGO,
+            &data
         ).await {
-            Ok(_f) => true,
-            Err(_e) => false
+            Ok(file_id) => Some(file_id),
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
         };
-        let result: StatusResponse = StatusResponse{
-            status: edit
+        stored.push(branding.logo_file_id.clone());
+    }
+    if let Some(favicon) = form.favicon {
+        branding.favicon_file_id = match store_branding_asset(
+            favicon,
+            BRANDING_FAVICON,
+            &data
+        ).await {
+            Ok(file_id) => Some(file_id),
+            Err(e) => {
+                discard_branding_assets(&stored);
+                return Err::<HttpResponse, YokaiErr>(
+                    YokaiErr::new(&e.to_string())
+                );
+            }
         };
-        Ok(HttpResponse::Ok().json(result))
+        stored.push(branding.favicon_file_id.clone());
     }
-    else {
-        Err::<HttpResponse, YokaiErr>(
-            YokaiErr::new("Requesting user is not an administrator.")
-        )
+    for (field, changed) in [
+        (BRANDING_LOGO, branding.logo_file_id != info.logo_file_id),
+        (BRANDING_FAVICON, branding.favicon_file_id != info.favicon_file_id)
+    ] {
+        if changed {
+            changes.push(field.to_string());
+        }
+    }
+    let mut tx: Transaction<'_, Postgres> = match data.pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            discard_branding_assets(&stored);
+            return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            );
+        }
+    };
+    let _: () = match edit_host_branding(
+        &branding,
+        &mut tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => {
+            discard_branding_assets(&stored);
+            return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            );
+        }
+    };
+    if !changes.is_empty() &&
+       let Err(e) = record_audit(
+            &user.username,
+            AUDIT_HOST_EDIT,
+            &None,
+            &Some(changes.join(", ")),
+            &request_ip(&req, &data.trusted_proxies),
+            &mut tx
+        ).await
+    {
+        discard_branding_assets(&stored);
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        );
+    }
+    let _: () = match tx.commit().await {
+        Ok(_f) => {},
+        Err(e) => {
+            discard_branding_assets(&stored);
+            return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            );
+        }
+    };
+    for (old_id, new_id) in [
+        (&info.logo_file_id, &branding.logo_file_id),
+        (&info.favicon_file_id, &branding.favicon_file_id)
+    ] {
+        if old_id != new_id {
+            discard_branding_assets(from_ref(old_id));
+        }
     }
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
 }
 
 #[post("/user/kick")]
 pub async fn kick_user_service(
     payload: Json<KickUserPayload>,
+    req: HttpRequest,
     data: Data<AppData>
 ) -> Result<HttpResponse, YokaiErr>{
     let admin: User = match get_user_by_token(
@@ -532,7 +1092,7 @@ pub async fn kick_user_service(
             YokaiErr::new(&e.to_string())
         )
     };
-    if admin.is_admin{
+    if has_permission(&admin, &Permission::ManageUsers){
         let user: User = match get_user_by_id(
             &payload.username,
             &data.pool
@@ -542,13 +1102,28 @@ pub async fn kick_user_service(
                 YokaiErr::new(&e.to_string())
             )
         };
-        let edit: bool = match delete_account(
+        if !outranks(&admin, &user){
+            return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new("Cannot act on a user with an equal or higher role.")
+            );
+        }
+        let edit: bool = kick_account(
+            &admin.username,
             &user.username,
+            &request_ip(&req, &data.trusted_proxies),
             &data.pool
-        ).await {
-            Ok(_f) => true,
-            Err(_e) => false
-        };
+        ).await.is_ok();
+        if edit {
+            let _: () = match end_live_sessions(
+                &user.username,
+                &data
+            ){
+                Ok(_f) => {},
+                Err(e) => return Err::<HttpResponse, YokaiErr>(
+                    YokaiErr::new(&e.to_string())
+                )
+            };
+        }
         let result: StatusResponse = StatusResponse{
             status: edit
         };
@@ -556,11 +1131,64 @@ pub async fn kick_user_service(
     }
     else {
         Err::<HttpResponse, YokaiErr>(
-            YokaiErr::new("Requesting user is not an administrator.")
+            YokaiErr::new("Requesting user lacks permission for this action.")
         )
     }
 }
 
+async fn kick_account(
+    admin: &str,
+    username: &str,
+    ip: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let mut tx: Transaction<'_, Postgres> = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let stored: Vec<String> = match purge_account(
+        username,
+        &mut tx
+    ).await {
+        Ok(stored) => stored,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match remove_account(
+        username,
+        &mut tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match record_audit(
+        admin,
+        AUDIT_USER_KICK,
+        &Some(username.to_string()),
+        &None,
+        ip,
+        &mut tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _commit: () = match tx.commit().await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    remove_stored_files(&stored);
+    Ok(())
+}
+
 #[post("/user/delete")]
 pub async fn delete_account_service(
     payload: Json<TokenOnlyPayload>,
@@ -613,12 +1241,15 @@ pub async fn user_contacts_service(
     };
     let mut contacts_vec: Vec<UserContact> = Vec::new();
     for contact in contacts {
-        // TODO
+        let available: bool = !is_suspended(&contact);
+        let pfp_url: Option<String> = contact.user_pfp_id
+            .map(|pfp_id| avatar_url(&pfp_id, AVATAR_VARIANT));
         let user_contact: UserContact = UserContact{
             username: contact.username,
             display_name: contact.display_name,
             description: contact.description,
-            pfp_url: contact.user_pfp_id
+            pfp_url,
+            available
         };
         contacts_vec.push(user_contact);        
     }
@@ -644,6 +1275,11 @@ pub async fn post_file_service(
             YokaiErr::new(&e.to_string())
         )
     };
+    if form.file.size as i64 > data.max_upload_size {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Upload size is not within the allowed limits.")
+        );
+    }
     let file_id: String = hash_string(
         &format!(
             "{}{}{}",
@@ -652,25 +1288,12 @@ pub async fn post_file_service(
             &rfc2282()
         )
     );
-    let mut path_buf: PathBuf = PathBuf::new();
-    let current: PathBuf = match current_dir(){
-        Ok(current) => current,
+    let mut path_buf: PathBuf = match storage_dir("user_files"){
+        Ok(path_buf) => path_buf,
         Err(e) => return Err::<HttpResponse, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    path_buf.push(current);
-    path_buf.push("user_files");
-    if !path_buf.exists(){         
-        let _created_dir: () = match create_dir(
-            &path_buf.as_path()
-        ){
-            Ok(_created_dir) => {},
-            Err(e) => return Err::<HttpResponse, YokaiErr>(
-                YokaiErr::new(&e.to_string())
-            )
-        };
-    }
     path_buf.push(&file_id);
     let _: () = match form.file.file.persist(path_buf.as_path()){
         Ok(_f) => {},
@@ -685,10 +1308,40 @@ pub async fn post_file_service(
         &data.pool
     ).await {
         Ok(_user_file) => true,
-        Err(_e) => false
+        Err(_e) => {
+            let _ = remove_file(path_buf.as_path());
+            false
+        }
+    };
+    let available: bool = if user_file {
+        match scan_user_file(
+            &file_id,
+            &data.clamav_socket,
+            &data.pool
+        ).await {
+            Ok(available) => available,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        }
+    }
+    else {
+        false
     };
+    if available {
+        let _variants: Vec<FileVariant> = match create_image_variants(
+            &file_id,
+            path_buf.as_path(),
+            &data.pool
+        ).await {
+            Ok(_variants) => _variants,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+    }
     let result: StatusResponse = StatusResponse{ 
-        status: user_file 
+        status: available 
     };
     Ok(HttpResponse::Ok().json(result))
 }
@@ -716,6 +1369,11 @@ pub async fn serve_file_service(
             YokaiErr::new(&e.to_string())
         )
     };
+    if is_quarantined(&file) {
+        return Err::<NamedFile, YokaiErr>(
+            YokaiErr::new("File has been quarantined.")
+        );
+    }
     let named_file = match NamedFile::open(file.file_path){
         Ok(named_file) => named_file,
         Err(e) => return Err::<NamedFile, YokaiErr>(
@@ -732,6 +1390,159 @@ pub async fn serve_file_service(
     }
 }
 
+#[get("/avatar/{file_id}/{variant}")]
+pub async fn serve_avatar_service(
+    path: Path<(String, String)>,
+    data: Data<AppData>
+) -> Result<NamedFile, YokaiErr>{
+    let (file_id, variant) = path.into_inner();
+    let in_use: bool = pfp_in_use(
+        &file_id,
+        &data.pool
+    ).await;
+    if !in_use {
+        return Err::<NamedFile, YokaiErr>(
+            YokaiErr::new("No such profile picture exists.")
+        );
+    }
+    let file_variant: FileVariant = match get_file_variant(
+        &file_id,
+        &variant,
+        &data.pool
+    ).await {
+        Ok(file_variant) => file_variant,
+        Err(e) => return Err::<NamedFile, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let named_file: NamedFile = match NamedFile::open(file_variant.file_path){
+        Ok(named_file) => named_file,
+        Err(e) => return Err::<NamedFile, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(named_file)
+}
+
+#[post("/file/link")]
+pub async fn file_link_service(
+    payload: Json<FilePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let file: UserFile = match get_file_by_id(
+        &payload.file_id,
+        &data.pool
+    ).await {
+        Ok(file) => file,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if is_quarantined(&file) {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("File has been quarantined.")
+        );
+    }
+    let shared: bool = file_shared_with(
+        &file.file_id,
+        &user.username,
+        &data.pool
+    ).await;
+    if user.username == file.file_owner || shared {
+        let expires: i64 = Utc::now().timestamp() + DOWNLOAD_LINK_TTL;
+        let signature: String = match sign_download(
+            &file.file_id,
+            &expires,
+            &data.signing_key
+        ){
+            Ok(signature) => signature,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let result: FileLinkResponse = FileLinkResponse{
+            file_url: format!(
+                "/files/download/{}?expires={}&signature={}",
+                &file.file_id,
+                expires,
+                &signature
+            ),
+            expires
+        };
+        Ok(HttpResponse::Ok().json(result))
+    }
+    else {
+        Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("File ownership could not be verified.")
+        )
+    }
+}
+
+#[get("/files/download/{file_id}")]
+pub async fn download_file_service(
+    path: Path<String>,
+    query: Query<DownloadQuery>,
+    req: HttpRequest,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let file_id: String = path.into_inner();
+    let verified: bool = verify_download(
+        &file_id,
+        &query.expires,
+        &query.signature,
+        &data.signing_key
+    );
+    if !verified {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Download link is invalid or has expired.")
+        );
+    }
+    let file: UserFile = match get_file_by_id(
+        &file_id,
+        &data.pool
+    ).await {
+        Ok(file) => file,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if is_quarantined(&file) {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("File has been quarantined.")
+        );
+    }
+    let named_file: NamedFile = match NamedFile::open(file.file_path){
+        Ok(named_file) => named_file,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let max_age: i64 = query.expires - Utc::now().timestamp();
+    let cache_control: HeaderValue = match HeaderValue::from_str(
+        &format!("private, max-age={}", max_age.max(0))
+    ){
+        Ok(cache_control) => cache_control,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut response: HttpResponse = named_file
+        .use_etag(true)
+        .use_last_modified(true)
+        .into_response(&req);
+    response.headers_mut().insert(CACHE_CONTROL, cache_control);
+    Ok(response)
+}
+
 #[post("/files/delete")]
 pub async fn delete_file_service(
     payload: Json<FilePayload>,
@@ -880,6 +1691,52 @@ pub async fn create_chat_service(
     }
 }
 
+pub async fn publish_message_event(
+    message: &Message,
+    events: &EventHub,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let chat: Chat = match get_chat_by_id(
+        &message.chat_id,
+        pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let participants: Vec<String> = match active_participants(
+        &chat,
+        pool
+    ).await {
+        Ok(participants) => participants,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let recipients: Vec<String> = match unmuted_users(
+        &chat.chat_id,
+        &participants,
+        pool
+    ).await {
+        Ok(recipients) => recipients,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let event: MessageEvent = MessageEvent{
+        chat_id: message.chat_id.clone(),
+        msg_id: message.msg_id.clone(),
+        sender: message.sender.clone(),
+        published: message.published.clone()
+    };
+    events.publish(
+        &recipients,
+        MESSAGE_EVENT,
+        &event
+    )
+}
+
 #[post("/message/send")]
 pub async fn send_message_service(
     payload: Json<SendMessagePayload>,
@@ -894,41 +1751,153 @@ pub async fn send_message_service(
             YokaiErr::new(&e.to_string())
         )
     };
-    let recv_exists: bool = user_exists(
-        &payload.receiver, 
-        &data.pool
-    ).await;
-    if recv_exists{
-        let encrypted: String = match encrypt_message(
+    if let Some(send_at) = &payload.send_at {
+        let scheduled: ScheduledMessage = match create_scheduled_message(
             &payload.msg,
-            &user.public_key
-        ){
-            Ok(encrypted) => encrypted,
-            Err(e) => return Err::<HttpResponse, YokaiErr>(
-                YokaiErr::new(&e.to_string())
-            )
-        };
-        let new_message: bool = match create_message(
-            &encrypted,
             &user.username,
             &payload.chat_id,
-            &payload.receiver,
             &payload.attachment,
+            &payload.reply_to,
+            send_at,
             &data.pool
         ).await {
-            Ok(_n) => true,
-            Err(_e) => false
+            Ok(scheduled) => scheduled,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
         };
-        let result: StatusResponse = StatusResponse{
-            status: new_message
+        let result: ScheduledIdResponse = ScheduledIdResponse{
+            schedule_id: scheduled.schedule_id
         };
-        Ok(HttpResponse::Ok().json(result))
+        return Ok(HttpResponse::Ok().json(result));
     }
-    else {
-        Err::<HttpResponse, YokaiErr>(
-            YokaiErr::new("Receiver does not exist.")
+    let new_message: bool = match create_message(
+        &payload.msg,
+        &user.username,
+        &payload.chat_id,
+        &payload.attachment,
+        &payload.reply_to,
+        &data.pool
+    ).await {
+        Ok(message) => {
+            let _: () = match publish_message_event(
+                &message,
+                &data.events,
+                &data.pool
+            ).await {
+                Ok(_f) => {},
+                Err(e) => return Err::<HttpResponse, YokaiErr>(
+                    YokaiErr::new(&e.to_string())
+                )
+            };
+            true
+        },
+        Err(_e) => false
+    };
+    let result: StatusResponse = StatusResponse{
+        status: new_message
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/message/edit")]
+pub async fn edit_message_service(
+    payload: Json<EditMessagePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _edited: Message = match edit_message(
+        &payload.msg_id,
+        &user.username,
+        &payload.msg,
+        &data.edit_window,
+        &data.pool
+    ).await {
+        Ok(edited) => edited,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/message/delete")]
+pub async fn delete_message_service(
+    payload: Json<MessagePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match tombstone_message(
+        &payload.msg_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/message/history")]
+pub async fn message_history_service(
+    payload: Json<MessagePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let edits: Vec<MessageEdit> = match get_message_edits(
+        &payload.msg_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(edits) => edits,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
         )
+    };
+    let mut revisions: Vec<MessageRevision> = Vec::new();
+    for edit in edits {
+        let revision: MessageRevision = MessageRevision{
+            content: edit.content,
+            edited: edit.edited
+        };
+        revisions.push(revision);
     }
+    let result: MessageHistoryResponse = MessageHistoryResponse{
+        revisions
+    };
+    Ok(HttpResponse::Ok().json(result))
 }
 
 #[post("/message/decrypt")]
@@ -959,3 +1928,3091 @@ pub async fn decrypt_message_service(
     };
     Ok(HttpResponse::Ok().json(result))
 }
+
+#[post("/user/chats")]
+pub async fn user_chats_service(
+    payload: Json<ChatListPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chats: Vec<Chat> = match get_user_chats(
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(chats) => chats,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let settings: HashMap<String, ChatSetting> = match get_user_chat_settings(
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(settings) => settings,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut summaries: Vec<ChatSummary> = Vec::new();
+    for chat in chats {
+        if awaiting_approval(&chat, &user.username){
+            continue;
+        }
+        let setting: Option<&ChatSetting> = settings.get(&chat.chat_id);
+        let archived: bool = setting.is_some_and(|setting| setting.archived);
+        if payload.archived.is_some_and(|wanted| wanted != archived){
+            continue;
+        }
+        let muted_until: Option<String> = setting
+            .and_then(|setting| setting.muted_until.clone());
+        let muted: bool = match &muted_until {
+            Some(until) => seconds_since(until).is_ok_and(|since| since < 0),
+            None => false
+        };
+        let participants: Vec<String> = match chat_participants(
+            &chat,
+            &data.pool
+        ).await {
+            Ok(participants) => participants,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let summary: ChatSummary = ChatSummary{
+            chat_id: chat.chat_id,
+            is_group: chat.is_group,
+            title: chat.title,
+            participants,
+            archived,
+            muted,
+            muted_until,
+            pinned: setting.is_some_and(|setting| setting.pinned),
+            nickname: setting.and_then(|setting| setting.nickname.clone())
+        };
+        summaries.push(summary);
+    }
+    summaries.sort_by_key(|summary| !summary.pinned);
+    let result: ChatsResponse = ChatsResponse{
+        chats: summaries
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/chat/messages")]
+pub async fn chat_messages_service(
+    payload: Json<ChatPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match get_chat_by_id(
+        &payload.chat_id,
+        &data.pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let participant: bool = is_chat_participant(
+        &chat,
+        &user.username,
+        &data.pool
+    ).await;
+    if !participant {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("User is not a member of this chat.")
+        );
+    }
+    let messages: Vec<Message> = match get_chat_messages(
+        &chat.chat_id,
+        &data.pool
+    ).await {
+        Ok(messages) => messages,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match mark_delivered(
+        &chat.chat_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat_messages: Vec<ChatMessage> = match render_messages(
+        &chat.chat_id,
+        messages,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(chat_messages) => chat_messages,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: ChatMessagesResponse = ChatMessagesResponse{
+        messages: chat_messages
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/chat/read")]
+pub async fn mark_read_service(
+    payload: Json<ReadPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match get_chat_by_id(
+        &payload.chat_id,
+        &data.pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let participant: bool = is_chat_participant(
+        &chat,
+        &user.username,
+        &data.pool
+    ).await;
+    if !participant {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("User is not a member of this chat.")
+        );
+    }
+    let _: () = match mark_read(
+        &chat.chat_id,
+        &payload.msg_id,
+        &user,
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/message/thread")]
+pub async fn message_thread_service(
+    payload: Json<MessagePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let message: Message = match get_message_by_id(
+        &payload.msg_id,
+        &data.pool
+    ).await {
+        Ok(message) => message,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match get_chat_by_id(
+        &message.chat_id,
+        &data.pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let participant: bool = is_chat_participant(
+        &chat,
+        &user.username,
+        &data.pool
+    ).await;
+    if !participant {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("User is not a member of this chat.")
+        );
+    }
+    let root_id: String = message.thread_root.unwrap_or(message.msg_id);
+    let messages: Vec<Message> = match get_thread_messages(
+        &root_id,
+        &data.pool
+    ).await {
+        Ok(messages) => messages,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match mark_delivered(
+        &chat.chat_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat_messages: Vec<ChatMessage> = match render_messages(
+        &chat.chat_id,
+        messages,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(chat_messages) => chat_messages,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: ChatMessagesResponse = ChatMessagesResponse{
+        messages: chat_messages
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/message/react")]
+pub async fn add_reaction_service(
+    payload: Json<ReactionPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let participants: Vec<String> = match message_participants(
+        &payload.msg_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(participants) => participants,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let added: bool = match add_reaction(
+        &payload.msg_id,
+        &user.username,
+        &payload.emoji,
+        &data.pool
+    ).await {
+        Ok(added) => added,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if added {
+        let message: Message = match get_message_by_id(
+            &payload.msg_id,
+            &data.pool
+        ).await {
+            Ok(message) => message,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let recipients: Vec<String> = match unmuted_users(
+            &message.chat_id,
+            &participants,
+            &data.pool
+        ).await {
+            Ok(recipients) => recipients,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let event: ReactionEvent = ReactionEvent{
+            chat_id: message.chat_id,
+            msg_id: message.msg_id,
+            username: user.username,
+            emoji: payload.emoji.clone(),
+            added: true
+        };
+        let _: () = match data.events.publish(
+            &recipients,
+            REACTION_EVENT,
+            &event
+        ){
+            Ok(_f) => {},
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+    }
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/message/unreact")]
+pub async fn remove_reaction_service(
+    payload: Json<ReactionPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let participants: Vec<String> = match message_participants(
+        &payload.msg_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(participants) => participants,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let removed: bool = match remove_reaction(
+        &payload.msg_id,
+        &user.username,
+        &payload.emoji,
+        &data.pool
+    ).await {
+        Ok(removed) => removed,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if removed {
+        let message: Message = match get_message_by_id(
+            &payload.msg_id,
+            &data.pool
+        ).await {
+            Ok(message) => message,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let recipients: Vec<String> = match unmuted_users(
+            &message.chat_id,
+            &participants,
+            &data.pool
+        ).await {
+            Ok(recipients) => recipients,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let event: ReactionEvent = ReactionEvent{
+            chat_id: message.chat_id,
+            msg_id: message.msg_id,
+            username: user.username,
+            emoji: payload.emoji.clone(),
+            added: false
+        };
+        let _: () = match data.events.publish(
+            &recipients,
+            REACTION_EVENT,
+            &event
+        ){
+            Ok(_f) => {},
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+    }
+    let result: StatusResponse = StatusResponse{
+        status: removed
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[get("/events")]
+pub async fn events_service(
+    req: HttpRequest,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let api_token: String = match get_header(&req, "Yokai-Api-Token"){
+        Ok(api_token) => api_token,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let user: User = match get_user_by_token(
+       &api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match data.presence.touch(
+        &user.username,
+        ONLINE_STATUS
+    ){
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let receiver: UnboundedReceiver<String> = match data.events.subscribe(
+        &user.username
+    ){
+        Ok(receiver) => receiver,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let stream = UnboundedReceiverStream::new(receiver)
+        .map(|event| Ok::<Bytes, YokaiErr>(Bytes::from(event)));
+    let response: HttpResponse = HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((CACHE_CONTROL, "no-cache"))
+        .streaming(stream);
+    Ok(response)
+}
+
+#[post("/presence/heartbeat")]
+pub async fn presence_heartbeat_service(
+    payload: Json<PresencePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match data.presence.touch(
+        &user.username,
+        &payload.status
+    ){
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/presence/contacts")]
+pub async fn contacts_presence_service(
+    payload: Json<TokenOnlyPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let contacts: Vec<User> = match get_user_contacts(
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(contacts) => contacts,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut presence: Vec<UserPresence> = Vec::new();
+    for contact in contacts {
+        let visible: bool = can_see_presence(
+            &user.username,
+            &contact,
+            &true
+        );
+        let user_presence: UserPresence = match data.presence.user_presence(
+            &contact,
+            &visible
+        ){
+            Ok(user_presence) => user_presence,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        presence.push(user_presence);
+    }
+    let result: PresenceResponse = PresenceResponse{
+        presence
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/presence/user")]
+pub async fn user_presence_service(
+    payload: Json<UserPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let subject: User = match get_user_by_id(
+        &payload.username,
+        &data.pool
+    ).await {
+        Ok(subject) => subject,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let contacts: Vec<User> = match get_user_contacts(
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(contacts) => contacts,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let is_contact: bool = contacts
+        .iter()
+        .any(|contact| contact.username == subject.username);
+    let visible: bool = can_see_presence(
+        &user.username,
+        &subject,
+        &is_contact
+    );
+    let result: UserPresence = match data.presence.user_presence(
+        &subject,
+        &visible
+    ){
+        Ok(result) => result,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/chat/typing")]
+pub async fn typing_service(
+    payload: Json<TypingPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match get_chat_by_id(
+        &payload.chat_id,
+        &data.pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let participants: Vec<String> = match active_participants(
+        &chat,
+        &data.pool
+    ).await {
+        Ok(participants) => participants,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !participants.contains(&user.username){
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("User is not a member of this chat.")
+        );
+    }
+    let _: () = match data.presence.set_typing(
+        &chat.chat_id,
+        &user.username,
+        &payload.typing
+    ){
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let others: Vec<String> = participants
+        .into_iter()
+        .filter(|participant| *participant != user.username)
+        .collect::<Vec<String>>();
+    let others: Vec<String> = match unmuted_users(
+        &chat.chat_id,
+        &others,
+        &data.pool
+    ).await {
+        Ok(others) => others,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let event: TypingEvent = TypingEvent{
+        chat_id: chat.chat_id,
+        username: user.username,
+        typing: payload.typing
+    };
+    let _: () = match data.events.publish(
+        &others,
+        TYPING_EVENT,
+        &event
+    ){
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/chat/typers")]
+pub async fn typers_service(
+    payload: Json<ChatPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match get_chat_by_id(
+        &payload.chat_id,
+        &data.pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let participant: bool = is_chat_participant(
+        &chat,
+        &user.username,
+        &data.pool
+    ).await;
+    if !participant {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("User is not a member of this chat.")
+        );
+    }
+    let usernames: Vec<String> = match data.presence.typers(&chat.chat_id){
+        Ok(usernames) => usernames,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: TypersResponse = TypersResponse{
+        usernames
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/group/create")]
+pub async fn create_group_service(
+    payload: Json<GroupCreatePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match create_group(
+        &user.username,
+        &payload.title,
+        &payload.members,
+        &data.pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: ChatIdResponse = ChatIdResponse{
+        chat_id: chat.chat_id
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/group/members")]
+pub async fn group_members_service(
+    payload: Json<ChatPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !is_chat_member(&payload.chat_id, &user.username, &data.pool).await {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("User is not a member of this chat.")
+        );
+    }
+    let members: Vec<ChatMember> = match get_chat_members(
+        &payload.chat_id,
+        &data.pool
+    ).await {
+        Ok(members) => members,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut group_members: Vec<GroupMember> = Vec::new();
+    for member in members {
+        let group_member: GroupMember = GroupMember{
+            username: member.username,
+            role: member.member_role,
+            joined: member.joined
+        };
+        group_members.push(group_member);
+    }
+    let result: GroupMembersResponse = GroupMembersResponse{
+        members: group_members
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/group/invite")]
+pub async fn group_invite_service(
+    payload: Json<GroupMemberPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let inviter: ChatMember = match get_chat_member(
+        &payload.chat_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(inviter) => inviter,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if inviter.member_role == MEMBER_ROLE {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Only owners and admins can invite members.")
+        );
+    }
+    if is_chat_member(&payload.chat_id, &payload.username, &data.pool).await {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("User is already a member of this chat.")
+        );
+    }
+    let added: bool = match add_chat_member(
+        &payload.chat_id,
+        &user.username,
+        &payload.username,
+        MEMBER_ROLE,
+        &data.pool
+    ).await {
+        Ok(_m) => true,
+        Err(_e) => false
+    };
+    let result: StatusResponse = StatusResponse{
+        status: added
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/group/remove")]
+pub async fn group_remove_service(
+    payload: Json<GroupMemberPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let remover: ChatMember = match get_chat_member(
+        &payload.chat_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(remover) => remover,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let target: ChatMember = match get_chat_member(
+        &payload.chat_id,
+        &payload.username,
+        &data.pool
+    ).await {
+        Ok(target) => target,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let allowed: bool = match remover.member_role.as_str() {
+        OWNER_ROLE => target.member_role != OWNER_ROLE,
+        ADMIN_ROLE => target.member_role == MEMBER_ROLE,
+        _ => false
+    };
+    if !allowed {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Insufficient role to remove this member.")
+        );
+    }
+    let removed: bool = match remove_chat_member(
+        &target.chat_id,
+        &target.username,
+        &data.pool
+    ).await {
+        Ok(_f) => true,
+        Err(_e) => false
+    };
+    let result: StatusResponse = StatusResponse{
+        status: removed
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/group/leave")]
+pub async fn group_leave_service(
+    payload: Json<ChatPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let left: bool = match remove_chat_member(
+        &payload.chat_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(_f) => true,
+        Err(_e) => false
+    };
+    let result: StatusResponse = StatusResponse{
+        status: left
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/group/role")]
+pub async fn group_role_service(
+    payload: Json<GroupRolePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let owner: ChatMember = match get_chat_member(
+        &payload.chat_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(owner) => owner,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if owner.member_role != OWNER_ROLE {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Only the owner can change roles.")
+        );
+    }
+    if payload.role != ADMIN_ROLE && payload.role != MEMBER_ROLE {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Role must be either admin or member.")
+        );
+    }
+    if payload.username == owner.username {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("The owner cannot change their own role.")
+        );
+    }
+    let edited: bool = match set_member_role(
+        &payload.chat_id,
+        &payload.username,
+        &payload.role,
+        &data.pool
+    ).await {
+        Ok(_f) => true,
+        Err(_e) => false
+    };
+    let result: StatusResponse = StatusResponse{
+        status: edited
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/uploads/create")]
+pub async fn upload_create_service(
+    payload: Json<UploadCreatePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let session_id: String = hash_string(
+        &format!(
+            "{}{}{}",
+            &payload.name,
+            &user.username,
+            &rfc2282()
+        )
+    );
+    let mut path_buf: PathBuf = match storage_dir("user_files/partial"){
+        Ok(path_buf) => path_buf,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    path_buf.push(&session_id);
+    let _: () = match File::create(path_buf.as_path()){
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let session: UploadSession = match create_upload_session(
+        &session_id,
+        &user.username,
+        &payload.name,
+        &path_buf.display().to_string(),
+        &payload.total_size,
+        &payload.checksum,
+        &data.max_upload_size,
+        &data.pool
+    ).await {
+        Ok(session) => session,
+        Err(e) => {
+            let _ = remove_file(path_buf.as_path());
+            return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        }
+    };
+    let result: UploadSessionResponse = UploadSessionResponse{
+        session_id: session.session_id,
+        offset: session.received,
+        total_size: session.total_size
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[patch("/uploads/{session_id}")]
+pub async fn upload_chunk_service(
+    path: Path<String>,
+    req: HttpRequest,
+    body: Bytes,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let api_token: String = match get_header(&req, "Yokai-Api-Token"){
+        Ok(api_token) => api_token,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let offset_str: String = match get_header(&req, "Upload-Offset"){
+        Ok(offset_str) => offset_str,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let offset: i64 = match offset_str.parse::<i64>(){
+        Ok(offset) => offset,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chunk_checksum: String = match get_header(&req, "Upload-Checksum"){
+        Ok(chunk_checksum) => chunk_checksum,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let user: User = match get_user_by_token(
+       &api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut tx: Transaction<'_, Postgres> = match data.pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let session: UploadSession = match lock_upload_session(
+        &path.into_inner(),
+        &mut tx
+    ).await {
+        Ok(session) => session,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if session.owner != user.username {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Upload ownership could not be verified.")
+        );
+    }
+    if offset != session.received {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Upload offset does not match.")
+        );
+    }
+    if hash_bytes(&body) != chunk_checksum.to_uppercase() {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Chunk checksum does not match.")
+        );
+    }
+    let new_offset: i64 = offset + body.len() as i64;
+    if new_offset > session.total_size {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Chunk exceeds the declared upload size.")
+        );
+    }
+    let mut file: File = match OpenOptions::new()
+        .write(true)
+        .open(&session.file_path)
+    {
+        Ok(file) => file,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: u64 = match file.seek(SeekFrom::Start(offset as u64)){
+        Ok(position) => position,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match file.write_all(&body){
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match advance_upload_session(
+        &session.session_id,
+        &offset,
+        &new_offset,
+        &mut tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _commit: () = match tx.commit().await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: UploadSessionResponse = UploadSessionResponse{
+        session_id: session.session_id,
+        offset: new_offset,
+        total_size: session.total_size
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/uploads/status")]
+pub async fn upload_status_service(
+    payload: Json<UploadSessionPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let session: UploadSession = match get_upload_session(
+        &payload.session_id,
+        &data.pool
+    ).await {
+        Ok(session) => session,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if session.owner == user.username {
+        let result: UploadSessionResponse = UploadSessionResponse{
+            session_id: session.session_id,
+            offset: session.received,
+            total_size: session.total_size
+        };
+        Ok(HttpResponse::Ok().json(result))
+    }
+    else {
+        Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Upload ownership could not be verified.")
+        )
+    }
+}
+
+#[post("/uploads/finish")]
+pub async fn upload_finish_service(
+    payload: Json<UploadSessionPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut tx: Transaction<'_, Postgres> = match data.pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let session: UploadSession = match lock_upload_session(
+        &payload.session_id,
+        &mut tx
+    ).await {
+        Ok(session) => session,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if session.owner != user.username {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Upload ownership could not be verified.")
+        );
+    }
+    if session.received != session.total_size {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Upload is not complete yet.")
+        );
+    }
+    let partial_path: PathBuf = PathBuf::from(&session.file_path);
+    let checksum: String = match block(
+        move || hash_file(partial_path.as_path())
+    ).await {
+        Ok(Ok(checksum)) => checksum,
+        Ok(Err(e)) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        ),
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if checksum != session.checksum {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Upload checksum does not match.")
+        );
+    }
+    let file_id: String = hash_string(
+        &format!(
+            "{}{}{}",
+            &session.file_name,
+            &user.username,
+            &rfc2282()
+        )
+    );
+    let mut path_buf: PathBuf = match storage_dir("user_files"){
+        Ok(path_buf) => path_buf,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    path_buf.push(&file_id);
+    let _: () = match rename(&session.file_path, path_buf.as_path()){
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let user_file: UserFile = match complete_upload_session(
+        &session,
+        &path_buf.display().to_string(),
+        &file_id,
+        &mut tx
+    ).await {
+        Ok(user_file) => user_file,
+        Err(e) => {
+            let _ = rename(path_buf.as_path(), &session.file_path);
+            return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            );
+        }
+    };
+    let _commit: () = match tx.commit().await {
+        Ok(_f) => {},
+        Err(e) => {
+            let _ = rename(path_buf.as_path(), &session.file_path);
+            return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            );
+        }
+    };
+    let available: bool = match scan_user_file(
+        &user_file.file_id,
+        &data.clamav_socket,
+        &data.pool
+    ).await {
+        Ok(available) => available,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !available {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("File has been quarantined.")
+        );
+    }
+    let _variants: Vec<FileVariant> = match create_image_variants(
+        &user_file.file_id,
+        path_buf.as_path(),
+        &data.pool
+    ).await {
+        Ok(_variants) => _variants,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: FileIdResponse = FileIdResponse{
+        file_id: user_file.file_id
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/uploads/cancel")]
+pub async fn upload_cancel_service(
+    payload: Json<UploadSessionPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let session: UploadSession = match get_upload_session(
+        &payload.session_id,
+        &data.pool
+    ).await {
+        Ok(session) => session,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if session.owner == user.username {
+        let del_op: bool = match delete_upload_session(
+            &session.session_id,
+            &data.pool
+        ).await {
+            Ok(_f) => true,
+            Err(_e) => false
+        };
+        let result: StatusResponse = StatusResponse{
+            status: del_op
+        };
+        Ok(HttpResponse::Ok().json(result))
+    }
+    else {
+        Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Upload ownership could not be verified.")
+        )
+    }
+}
+
+#[post("/admin/storage/report")]
+pub async fn storage_report_service(
+    payload: Json<TokenOnlyPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if has_permission(&user, &Permission::ViewStorage){
+        let report: StorageReportResponse = match collect_orphaned_files(
+            &true,
+            &data.pool
+        ).await {
+            Ok(report) => report,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        Ok(HttpResponse::Ok().json(report))
+    }
+    else {
+        Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Requesting user lacks permission for this action.")
+        )
+    }
+}
+
+#[post("/chat/timer")]
+pub async fn chat_timer_service(
+    payload: Json<ChatTimerPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match get_chat_by_id(
+        &payload.chat_id,
+        &data.pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if chat.is_group {
+        let member: ChatMember = match get_chat_member(
+            &chat.chat_id,
+            &user.username,
+            &data.pool
+        ).await {
+            Ok(member) => member,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        if member.member_role == MEMBER_ROLE {
+            return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new("Only group admins can change the timer.")
+            );
+        }
+    }
+    else if !is_chat_participant(&chat, &user.username, &data.pool).await {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("User is not a member of this chat.")
+        );
+    }
+    let _: () = match set_chat_timer(
+        &chat.chat_id,
+        &user.username,
+        &payload.ttl,
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/message/scheduled")]
+pub async fn scheduled_messages_service(
+    payload: Json<TokenOnlyPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let queued: Vec<ScheduledMessage> = match get_user_scheduled_messages(
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(queued) => queued,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let messages: Vec<ScheduledSummary> = queued
+        .into_iter()
+        .map(
+            |scheduled| ScheduledSummary{
+                schedule_id: scheduled.schedule_id,
+                chat_id: scheduled.chat_id,
+                content: scheduled.content,
+                attachment: scheduled.attachment,
+                reply_to: scheduled.reply_to,
+                send_at: scheduled.send_at,
+                created: scheduled.created,
+                attempts: scheduled.attempts,
+                last_error: scheduled.last_error,
+                failed: scheduled.failed
+            }
+        )
+        .collect::<Vec<ScheduledSummary>>();
+    let result: ScheduledMessagesResponse = ScheduledMessagesResponse{
+        messages
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/message/unschedule")]
+pub async fn unschedule_message_service(
+    payload: Json<ScheduledPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match cancel_scheduled_message(
+        &payload.schedule_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/message/pin")]
+pub async fn pin_message_service(
+    payload: Json<MessagePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _pin: PinnedMessage = match pin_message(
+        &payload.msg_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(pin) => pin,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/message/unpin")]
+pub async fn unpin_message_service(
+    payload: Json<MessagePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match unpin_message(
+        &payload.msg_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/chat/pins")]
+pub async fn chat_pins_service(
+    payload: Json<ChatPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match get_chat_by_id(
+        &payload.chat_id,
+        &data.pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !is_chat_participant(&chat, &user.username, &data.pool).await {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("User is not a member of this chat.")
+        );
+    }
+    let pinned: Vec<PinnedMessage> = match get_chat_pins(
+        &chat.chat_id,
+        &data.pool
+    ).await {
+        Ok(pinned) => pinned,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut pins: Vec<PinSummary> = Vec::new();
+    for pin in pinned {
+        let msg: Message = match get_message_by_id(
+            &pin.msg_id,
+            &data.pool
+        ).await {
+            Ok(msg) => msg,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let rendered: Vec<ChatMessage> = match render_messages(
+            &chat.chat_id,
+            vec![msg],
+            &user.username,
+            &data.pool
+        ).await {
+            Ok(rendered) => rendered,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        for message in rendered {
+            pins.push(
+                PinSummary{
+                    pinned_by: pin.pinned_by.clone(),
+                    pinned: pin.pinned.clone(),
+                    message
+                }
+            );
+        }
+    }
+    let result: PinsResponse = PinsResponse{
+        pins
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/message/star")]
+pub async fn star_message_service(
+    payload: Json<MessagePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _star: StarredMessage = match star_message(
+        &payload.msg_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(star) => star,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/message/unstar")]
+pub async fn unstar_message_service(
+    payload: Json<MessagePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match unstar_message(
+        &payload.msg_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/user/starred")]
+pub async fn starred_messages_service(
+    payload: Json<TokenOnlyPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let starred: Vec<StarredMessage> = match get_user_stars(
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(starred) => starred,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut stars: Vec<StarSummary> = Vec::new();
+    for star in starred {
+        let msg: Message = match get_message_by_id(
+            &star.msg_id,
+            &data.pool
+        ).await {
+            Ok(msg) => msg,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let chat_id: String = msg.chat_id.clone();
+        let rendered: Vec<ChatMessage> = match render_messages(
+            &chat_id,
+            vec![msg],
+            &user.username,
+            &data.pool
+        ).await {
+            Ok(rendered) => rendered,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        for message in rendered {
+            stars.push(
+                StarSummary{
+                    chat_id: chat_id.clone(),
+                    starred: star.starred.clone(),
+                    message
+                }
+            );
+        }
+    }
+    let result: StarsResponse = StarsResponse{
+        stars
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/chat/edit/archived")]
+pub async fn chat_archived_service(
+    payload: Json<ChatTogglePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match get_member_chat(
+        &payload.chat_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match set_chat_archived(
+        &chat.chat_id,
+        &user.username,
+        &payload.enabled,
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/chat/edit/muted")]
+pub async fn chat_muted_service(
+    payload: Json<ChatMutePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match get_member_chat(
+        &payload.chat_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match set_chat_muted(
+        &chat.chat_id,
+        &user.username,
+        &payload.muted_until,
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/chat/edit/pinned")]
+pub async fn chat_pinned_service(
+    payload: Json<ChatTogglePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match get_member_chat(
+        &payload.chat_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match set_chat_pinned(
+        &chat.chat_id,
+        &user.username,
+        &payload.enabled,
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/chat/edit/nickname")]
+pub async fn chat_nickname_service(
+    payload: Json<ChatNicknamePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match get_member_chat(
+        &payload.chat_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match set_chat_nickname(
+        &chat.chat_id,
+        &user.username,
+        &payload.nickname,
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/chat/open")]
+pub async fn open_chat_service(
+    payload: Json<ChatCreatePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match open_chat(
+        &user.username,
+        &payload.receiver,
+        &data.pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: ChatIdResponse = ChatIdResponse{
+        chat_id: chat.chat_id
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/user/block")]
+pub async fn block_user_service(
+    payload: Json<UserPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _block: UserBlock = match block_user(
+        &user.username,
+        &payload.username,
+        &data.pool
+    ).await {
+        Ok(block) => block,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/user/unblock")]
+pub async fn unblock_user_service(
+    payload: Json<UserPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match unblock_user(
+        &user.username,
+        &payload.username,
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/user/blocks")]
+pub async fn user_blocks_service(
+    payload: Json<TokenOnlyPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let user_blocks: Vec<UserBlock> = match get_user_blocks(
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(user_blocks) => user_blocks,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let blocks: Vec<BlockSummary> = user_blocks
+        .into_iter()
+        .map(
+            |block| BlockSummary{
+                username: block.blocked,
+                created: block.created
+            }
+        )
+        .collect::<Vec<BlockSummary>>();
+    let result: BlocksResponse = BlocksResponse{
+        blocks
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/chat/requests")]
+pub async fn chat_requests_service(
+    payload: Json<TokenOnlyPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chats: Vec<Chat> = match get_chat_requests(
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(chats) => chats,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut requests: Vec<ChatRequest> = Vec::new();
+    for chat in chats {
+        let held: Vec<Message> = match get_chat_messages(
+            &chat.chat_id,
+            &data.pool
+        ).await {
+            Ok(held) => held,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let request: ChatRequest = ChatRequest{
+            chat_id: chat.chat_id,
+            sender: chat.sender,
+            started: chat.started,
+            held: held.len()
+        };
+        requests.push(request);
+    }
+    let result: ChatRequestsResponse = ChatRequestsResponse{
+        requests
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/chat/accept")]
+pub async fn accept_chat_service(
+    payload: Json<ChatPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match accept_chat_request(
+        &payload.chat_id,
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/chat/decline")]
+pub async fn decline_chat_service(
+    payload: Json<ChatDeclinePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match decline_chat_request(
+        &payload.chat_id,
+        &user.username,
+        &payload.block,
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/report/user")]
+pub async fn report_user_service(
+    payload: Json<ReportUserPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let report: Report = match create_report(
+        &user.username,
+        &payload.username,
+        &None,
+        &payload.reason,
+        &None,
+        &data.pool
+    ).await {
+        Ok(report) => report,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: ReportIdResponse = ReportIdResponse{
+        report_id: report.report_id
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/report/message")]
+pub async fn report_message_service(
+    payload: Json<ReportMessagePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let user: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let report: Report = match report_message(
+        &user,
+        &payload.msg_id,
+        &payload.reason,
+        &payload.disclose,
+        &data.pool
+    ).await {
+        Ok(report) => report,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: ReportIdResponse = ReportIdResponse{
+        report_id: report.report_id
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/admin/reports")]
+pub async fn admin_reports_service(
+    payload: Json<ReportQueuePayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let admin: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(admin) => admin,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !has_permission(&admin, &Permission::ReviewReports){
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Requesting user lacks permission for this action.")
+        );
+    }
+    let page: i64 = payload.page.unwrap_or(1).max(1);
+    let per_page: i64 = payload.per_page
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let total: i64 = match count_reports(
+        &payload.status,
+        &data.pool
+    ).await {
+        Ok(total) => total,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let queue: Vec<Report> = match get_reports(
+        &payload.status,
+        &per_page,
+        &((page - 1) * per_page),
+        &data.pool
+    ).await {
+        Ok(queue) => queue,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let reports: Vec<ReportSummary> = queue
+        .into_iter()
+        .map(
+            |report| ReportSummary{
+                report_id: report.report_id,
+                reporter: report.reporter,
+                reported_user: report.reported_user,
+                msg_id: report.msg_id,
+                reason: report.reason,
+                disclosed: report.disclosed,
+                status: report.status,
+                created: report.created,
+                resolved: report.resolved,
+                resolved_by: report.resolved_by
+            }
+        )
+        .collect::<Vec<ReportSummary>>();
+    let result: ReportsResponse = ReportsResponse{
+        reports,
+        page,
+        per_page,
+        total
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/admin/reports/resolve")]
+pub async fn resolve_report_service(
+    payload: Json<ResolveReportPayload>,
+    req: HttpRequest,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let admin: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(admin) => admin,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !has_permission(&admin, &Permission::ReviewReports){
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Requesting user lacks permission for this action.")
+        );
+    }
+    if payload.action == ACTION_KICK && !has_permission(&admin, &Permission::ManageUsers){
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Requesting user lacks permission for this action.")
+        );
+    }
+    let _: () = match resolve_report(
+        &payload.report_id,
+        &admin.username,
+        &payload.action,
+        &payload.note,
+        &request_ip(&req, &data.trusted_proxies),
+        &data.pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if payload.action == ACTION_SUSPEND || payload.action == ACTION_KICK {
+        let report: Report = match get_report(
+            &payload.report_id,
+            &data.pool
+        ).await {
+            Ok(report) => report,
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let _: () = match end_live_sessions(
+            &report.reported_user,
+            &data
+        ){
+            Ok(_f) => {},
+            Err(e) => return Err::<HttpResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+    }
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+fn end_live_sessions(
+    username: &str,
+    data: &Data<AppData>
+) -> Result<(), YokaiErr>{
+    let _: () = match data.events.disconnect(username){
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    data.presence.forget(username)
+}
+
+#[post("/admin/suspend")]
+pub async fn suspend_user_service(
+    payload: Json<SuspendUserPayload>,
+    req: HttpRequest,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let admin: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(admin) => admin,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !has_permission(&admin, &Permission::SuspendUsers){
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Requesting user lacks permission for this action.")
+        );
+    }
+    if admin.username == payload.username {
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Cannot suspend yourself.")
+        );
+    }
+    let mut tx: Transaction<'_, Postgres> = match data.pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match suspend_user(
+        &admin.username,
+        &payload.username,
+        &payload.reason,
+        &payload.until,
+        &mut tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match record_audit(
+        &admin.username,
+        AUDIT_USER_SUSPEND,
+        &Some(payload.username.clone()),
+        &Some(payload.reason.clone()),
+        &request_ip(&req, &data.trusted_proxies),
+        &mut tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match tx.commit().await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match end_live_sessions(
+        &payload.username,
+        &data
+    ){
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/admin/unsuspend")]
+pub async fn unsuspend_user_service(
+    payload: Json<KickUserPayload>,
+    req: HttpRequest,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let admin: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(admin) => admin,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !has_permission(&admin, &Permission::SuspendUsers){
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Requesting user lacks permission for this action.")
+        );
+    }
+    let mut tx: Transaction<'_, Postgres> = match data.pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match unsuspend_user(
+        &admin.username,
+        &payload.username,
+        &mut tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match record_audit(
+        &admin.username,
+        AUDIT_USER_UNSUSPEND,
+        &Some(payload.username.clone()),
+        &None,
+        &request_ip(&req, &data.trusted_proxies),
+        &mut tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match tx.commit().await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/admin/role")]
+pub async fn user_role_service(
+    payload: Json<UserRolePayload>,
+    req: HttpRequest,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let admin: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(admin) => admin,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !has_permission(&admin, &Permission::ManageRoles){
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Requesting user lacks permission for this action.")
+        );
+    }
+    let mut tx: Transaction<'_, Postgres> = match data.pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match set_user_role(
+        &payload.username,
+        &payload.role,
+        &mut tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match record_audit(
+        &admin.username,
+        AUDIT_USER_ROLE,
+        &Some(payload.username.clone()),
+        &Some(payload.role.clone()),
+        &request_ip(&req, &data.trusted_proxies),
+        &mut tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match tx.commit().await {
+        Ok(_f) => {},
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: StatusResponse = StatusResponse{
+        status: true
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/admin/audit")]
+pub async fn audit_log_service(
+    payload: Json<AuditQueryPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let admin: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(admin) => admin,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !has_permission(&admin, &Permission::ViewAudit){
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Requesting user lacks permission for this action.")
+        );
+    }
+    let page: i64 = payload.page.unwrap_or(1).max(1);
+    let per_page: i64 = payload.per_page
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let total: i64 = match count_audit_entries(
+        &payload.actor,
+        &payload.action,
+        &data.pool
+    ).await {
+        Ok(total) => total,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let records: Vec<AuditEntry> = match get_audit_entries(
+        &payload.actor,
+        &payload.action,
+        &Some(per_page),
+        &((page - 1) * per_page),
+        &data.pool
+    ).await {
+        Ok(records) => records,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let entries: Vec<AuditEntrySummary> = records
+        .into_iter()
+        .map(summarize_audit_entry)
+        .collect::<Vec<AuditEntrySummary>>();
+    let result: AuditLogResponse = AuditLogResponse{
+        entries,
+        page,
+        per_page,
+        total
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/admin/audit/export")]
+pub async fn audit_export_service(
+    payload: Json<AuditQueryPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let admin: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(admin) => admin,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !has_permission(&admin, &Permission::ViewAudit){
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Requesting user lacks permission for this action.")
+        );
+    }
+    let (sender, receiver) = channel::<Result<Bytes, YokaiErr>>(1);
+    spawn(
+        stream_audit_pages(
+            payload.actor.clone(),
+            payload.action.clone(),
+            sender,
+            data.pool.clone()
+        )
+    );
+    Ok(
+        HttpResponse::Ok()
+            .content_type("application/x-ndjson")
+            .streaming(ReceiverStream::new(receiver))
+    )
+}
+
+async fn stream_audit_pages(
+    actor: Option<String>,
+    action: Option<String>,
+    sender: Sender<Result<Bytes, YokaiErr>>,
+    pool: Pool<Postgres>
+) {
+    let mut before: Option<i64> = None;
+    loop {
+        let records: Vec<AuditEntry> = match get_audit_page(
+            &actor,
+            &action,
+            &before,
+            &MAX_PAGE_SIZE,
+            &pool
+        ).await {
+            Ok(records) => records,
+            Err(e) => {
+                let _ = sender.send(Err::<Bytes, YokaiErr>(e)).await;
+                return;
+            }
+        };
+        before = match records.last(){
+            Some(record) => Some(record.seq),
+            None => return
+        };
+        let mut lines: String = String::new();
+        for record in records {
+            let line: String = match serde_json::to_string(
+                &summarize_audit_entry(record)
+            ){
+                Ok(line) => line,
+                Err(e) => {
+                    let _ = sender.send(
+                        Err::<Bytes, YokaiErr>(YokaiErr::new(&e.to_string()))
+                    ).await;
+                    return;
+                }
+            };
+            lines.push_str(&line);
+            lines.push('\n');
+        }
+        if sender.send(Ok::<Bytes, YokaiErr>(Bytes::from(lines))).await.is_err(){
+            return;
+        }
+    }
+}
+
+fn summarize_audit_entry(
+    entry: AuditEntry
+) -> AuditEntrySummary {
+    AuditEntrySummary{
+        seq: entry.seq,
+        actor: entry.actor,
+        action: entry.action,
+        target: entry.target,
+        details: entry.details,
+        ip: entry.ip,
+        created: entry.created
+    }
+}
+
+#[post("/admin/users")]
+pub async fn user_directory_service(
+    payload: Json<UserDirectoryPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let admin: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(admin) => admin,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !has_permission(&admin, &Permission::ViewUsers){
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Requesting user lacks permission for this action.")
+        );
+    }
+    let page: i64 = payload.page.unwrap_or(1).max(1);
+    let per_page: i64 = payload.per_page
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let total: i64 = match count_users(
+        &payload.search,
+        &data.pool
+    ).await {
+        Ok(total) => total,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let found: Vec<User> = match search_users(
+        &payload.search,
+        &per_page,
+        &((page - 1) * per_page),
+        &data.pool
+    ).await {
+        Ok(found) => found,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let users: Vec<DirectoryUser> = found
+        .into_iter()
+        .map(
+            |user| DirectoryUser{
+                suspended: is_suspended(&user),
+                username: user.username,
+                display_name: user.display_name,
+                user_role: user.user_role,
+                last_login: user.last_login
+            }
+        )
+        .collect::<Vec<DirectoryUser>>();
+    let result: UserDirectoryResponse = UserDirectoryResponse{
+        users,
+        page,
+        per_page,
+        total
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/admin/users/stats")]
+pub async fn user_stats_service(
+    payload: Json<UserPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let admin: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(admin) => admin,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !has_permission(&admin, &Permission::ViewUsers){
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Requesting user lacks permission for this action.")
+        );
+    }
+    let user: User = match get_user_by_id(
+        &payload.username,
+        &data.pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chats: Vec<Chat> = match get_user_chats(
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(chats) => chats,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let message_count: i64 = match count_user_messages(
+        &user.username,
+        &data.pool
+    ).await {
+        Ok(message_count) => message_count,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let storage: i64 = match storage_used(
+        &Some(user.username.clone()),
+        &data.pool
+    ).await {
+        Ok(storage) => storage,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: UserStatsResponse = UserStatsResponse{
+        username: user.username,
+        chat_count: chats.len(),
+        message_count,
+        storage_used: storage as u64,
+        last_login: user.last_login
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[post("/admin/stats")]
+pub async fn instance_stats_service(
+    payload: Json<TokenOnlyPayload>,
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let admin: User = match get_user_by_token(
+       &payload.api_token,
+       &data.pool
+    ).await {
+        Ok(admin) => admin,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !has_permission(&admin, &Permission::ViewUsers){
+        return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new("Requesting user lacks permission for this action.")
+        );
+    }
+    let users: i64 = match count_users(
+        &None,
+        &data.pool
+    ).await {
+        Ok(users) => users,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let suspended: Vec<User> = match get_suspended_users(
+        &data.pool
+    ).await {
+        Ok(suspended) => suspended,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let direct_chats: i64 = match count_chats(
+        &false,
+        &data.pool
+    ).await {
+        Ok(direct_chats) => direct_chats,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let group_chats: i64 = match count_chats(
+        &true,
+        &data.pool
+    ).await {
+        Ok(group_chats) => group_chats,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let messages: i64 = match count_messages(
+        &data.pool
+    ).await {
+        Ok(messages) => messages,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let files: i64 = match count_files(
+        &None,
+        &data.pool
+    ).await {
+        Ok(files) => files,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let storage: i64 = match storage_used(
+        &None,
+        &data.pool
+    ).await {
+        Ok(storage) => storage,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: InstanceStatsResponse = InstanceStatsResponse{
+        users,
+        suspended_users: suspended.len(),
+        direct_chats,
+        group_chats,
+        messages,
+        files: files as usize,
+        storage_used: storage as u64
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[get("/instance")]
+pub async fn instance_info_service(
+    data: Data<AppData>
+) -> Result<HttpResponse, YokaiErr>{
+    let info: HostInformation = match get_host_info(
+        &data.pool
+    ).await {
+        Ok(info) => info,
+        Err(e) => return Err::<HttpResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let result: InstanceInfoResponse = InstanceInfoResponse{
+        hostname: info.hostname,
+        primary_color: info.primary_color,
+        secondary_color: info.secondary_color,
+        tertiary_color: info.tertiary_color,
+        instance_name: info.instance_name,
+        description: info.description,
+        logo_url: info.logo_file_id
+            .map(|_id| format!("/instance/{}", BRANDING_LOGO)),
+        favicon_url: info.favicon_file_id
+            .map(|_id| format!("/instance/{}", BRANDING_FAVICON)),
+        terms: info.terms,
+        privacy_policy: info.privacy_policy,
+        contact_address: info.contact_address,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        registration_mode: REGISTRATION_MODE.to_string(),
+        max_message_bytes: MAX_MESSAGE_BYTES,
+        max_upload_size: data.max_upload_size,
+        upload_chunk_limit: UPLOAD_CHUNK_LIMIT,
+        edit_window: data.edit_window,
+        encryption_schemes: ENCRYPTION_SCHEMES
+            .iter()
+            .map(|scheme| scheme.to_string())
+            .collect::<Vec<String>>()
+    };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[get("/instance/{asset}")]
+pub async fn instance_asset_service(
+    path: Path<String>,
+    data: Data<AppData>
+) -> Result<NamedFile, YokaiErr>{
+    let asset: String = path.into_inner();
+    let info: HostInformation = match get_host_info(
+        &data.pool
+    ).await {
+        Ok(info) => info,
+        Err(e) => return Err::<NamedFile, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let file_id: Option<String> = match asset.as_str() {
+        BRANDING_LOGO => info.logo_file_id,
+        BRANDING_FAVICON => info.favicon_file_id,
+        _ => return Err::<NamedFile, YokaiErr>(
+            YokaiErr::new("No such instance asset exists.")
+        )
+    };
+    let path: PathBuf = match file_id {
+        Some(file_id) => match branding_path(&file_id){
+            Ok(path) => path,
+            Err(e) => return Err::<NamedFile, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        },
+        None => return Err::<NamedFile, YokaiErr>(
+            YokaiErr::new("No such instance asset has been set.")
+        )
+    };
+    let named_file: NamedFile = match NamedFile::open(path){
+        Ok(named_file) => named_file,
+        Err(e) => return Err::<NamedFile, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(named_file)
+}
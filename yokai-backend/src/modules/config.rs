@@ -9,6 +9,11 @@ use super::units::Config;
 use super::utils::check_username;
 use super::utils::check_password;
 use super::utils::check_color_str;
+use super::utils::generate_secret;
+
+pub const DEFAULT_MAX_UPLOAD_SIZE: i64 = 1073741824;
+
+pub const DEFAULT_EDIT_WINDOW: i64 = 900;
 
 pub fn get_config() -> Result<Config, YokaiErr>{
     let db_url: String = match var("YOKAI_DB_URL"){
@@ -95,6 +100,42 @@ pub fn get_config() -> Result<Config, YokaiErr>{
             YokaiErr::new(&e.to_string())
         )
     };
+    let max_upload_size: i64 = match var("YOKAI_MAX_UPLOAD_SIZE"){
+        Ok(max_upload_size) => match max_upload_size.parse::<i64>(){
+            Ok(max_upload_size) => max_upload_size,
+            Err(e) => return Err::<Config, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        },
+        Err(_e) => DEFAULT_MAX_UPLOAD_SIZE
+    };
+    let signing_key: String = match var("YOKAI_SIGNING_KEY"){
+        Ok(signing_key) => signing_key,
+        Err(_e) => match generate_secret(){
+            Ok(signing_key) => signing_key,
+            Err(e) => return Err::<Config, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        }
+    };
+    let clamav_socket: Option<String> = var("YOKAI_CLAMAV_SOCKET").ok();
+    let edit_window: i64 = match var("YOKAI_EDIT_WINDOW"){
+        Ok(edit_window) => match edit_window.parse::<i64>(){
+            Ok(edit_window) => edit_window,
+            Err(e) => return Err::<Config, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        },
+        Err(_e) => DEFAULT_EDIT_WINDOW
+    };
+    let trusted_proxies: Vec<String> = match var("YOKAI_TRUSTED_PROXIES"){
+        Ok(trusted_proxies) => trusted_proxies
+            .split(',')
+            .map(|proxy| proxy.trim().to_string())
+            .filter(|proxy| !proxy.is_empty())
+            .collect::<Vec<String>>(),
+        Err(_e) => Vec::new()
+    };
     if check_username(&admin_username) &&
        check_password(&admin_password) &&
        check_color_str(&primary_color) &&
@@ -105,20 +146,25 @@ pub fn get_config() -> Result<Config, YokaiErr>{
        check_color_str(&admin_tertiary_color)
     {
         let config_vars: Config = Config {
-            db_url: db_url,
-            hostname: hostname,
-            app_host: app_host,
-            app_port: app_port,
-            primary_color: primary_color,
-            secondary_color: secondary_color,
-            tertiary_color: tertiary_color,
-            admin_username: admin_username,
-            admin_password: admin_password,
-            admin_description: admin_description,
-            admin_display_name: admin_display_name,
-            admin_primary_color: admin_primary_color,
-            admin_secondary_color: admin_secondary_color,
-            admin_tertiary_color: admin_tertiary_color
+            db_url,
+            hostname,
+            app_host,
+            app_port,
+            primary_color,
+            secondary_color,
+            tertiary_color,
+            admin_username,
+            admin_password,
+            admin_description,
+            admin_display_name,
+            admin_primary_color,
+            admin_secondary_color,
+            admin_tertiary_color,
+            max_upload_size,
+            signing_key,
+            clamav_socket,
+            edit_window,
+            trusted_proxies
         };
         Ok(config_vars)
     }
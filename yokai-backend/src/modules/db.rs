@@ -8,6 +8,9 @@ use sqlx::query;
 use bcrypt::hash;
 use sqlx::query_as;
 use bcrypt::verify;
+use std::path::Path;
+use sqlx::Transaction;
+use sqlx::query_scalar;
 use super::models::User;
 use super::models::Chat;
 use super::err::YokaiErr;
@@ -15,25 +18,73 @@ use bcrypt::DEFAULT_COST;
 use std::fs::remove_file;
 use super::utils::rfc2282;
 use super::units::KeyPair;
+use super::models::Report;
+use super::roles::outranks;
 use super::models::Message;
 use super::models::UserFile;
+use super::utils::file_size;
+use super::models::UserBlock;
+use super::units::OWNER_ROLE;
+use super::units::ADMIN_ROLE;
 use sqlx::postgres::Postgres;
+use super::roles::ROLE_ADMIN;
+use super::models::AuditEntry;
+use super::units::REPORT_OPEN;
+use super::units::ACTION_KICK;
+use super::responses::Receipt;
+use super::utils::check_emoji;
+use std::collections::HashMap;
+use super::units::MEMBER_ROLE;
+use super::models::MessageKey;
+use super::models::ChatMember;
 use super::utils::hash_string;
 use super::models::InviteCode;
+use super::utils::is_suspended;
+use super::models::ChatSetting;
+use super::units::MESSAGE_TTLS;
+use super::models::MessageEdit;
+use super::models::FileVariant;
+use super::utils::like_pattern;
+use super::roles::is_valid_role;
+use super::utils::rfc2822_after;
+use super::utils::seconds_since;
 use super::models::UserAPIToken;
 use super::utils::check_message;
+use super::units::ACTION_SUSPEND;
+use super::units::ACTION_DISMISS;
+use super::models::PinnedMessage;
+use super::models::UploadSession;
 use super::utils::check_username;
 use super::utils::check_password;
+use super::utils::decrypt_message;
+use super::units::REPORT_RESOLVED;
+use super::models::StarredMessage;
+use super::responses::ChatMessage;
+use super::utils::generate_secret;
 use super::utils::check_color_str;
 use super::utils::encrypt_message;
+use super::units::MAX_OPEN_UPLOADS;
+use super::scanner::is_quarantined;
+use super::units::REPORT_DISMISSED;
+use super::models::MessageReaction;
 use super::utils::generate_keypair;
 use super::models::HostInformation;
+use super::units::MAX_MESSAGE_BYTES;
+use super::models::ScheduledMessage;
+use super::units::MAX_NICKNAME_LENGTH;
+use super::responses::ReactionSummary;
+use super::units::AUDIT_REPORT_RESOLVE;
+use super::presence::VISIBLE_TO_NOBODY;
+use super::units::ACTION_DELETE_MESSAGE;
+use super::presence::VISIBLE_TO_CONTACTS;
+use super::presence::VISIBLE_TO_EVERYONE;
 
 // used.
+#[allow(clippy::too_many_arguments)]
 pub async fn create_user(
     username: &str,
     password: &str,
-    is_admin: &bool,
+    role: &str,
     description: &str,
     display_name: &str,
     primary_color: &str,
@@ -64,7 +115,6 @@ pub async fn create_user(
         let obj: User = User {
             username: username.to_string(),
             password: hashed_pwd,
-            is_admin: *is_admin,
             public_key: pair.public_key,
             private_key: pair.private_key,
             description: description.to_string(),
@@ -72,13 +122,21 @@ pub async fn create_user(
             primary_color: primary_color.to_string(),
             tertiary_color: tertiary_color.to_string(),
             secondary_color: secondary_color.to_string(),
-            user_pfp_id: user_pfp_id.to_owned()
+            user_pfp_id: user_pfp_id.to_owned(),
+            read_receipts: true,
+            last_seen_visibility: VISIBLE_TO_EVERYONE.to_string(),
+            require_approval: false,
+            suspended_at: None,
+            suspended_until: None,
+            suspension_reason: None,
+            user_role: role.to_string(),
+            last_login: None
         };
-        let _insert_op = match query!(
-            "INSERT INTO users (username, password, is_admin, public_key, private_key, description, display_name, primary_color, tertiary_color, secondary_color, user_pfp_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+        let _insert_op: () = match query!(
+            "INSERT INTO users (username, password, user_role, public_key, private_key, description, display_name, primary_color, tertiary_color, secondary_color, user_pfp_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
             obj.username,
             obj.password,
-            obj.is_admin,
+            obj.user_role,
             obj.public_key,
             obj.private_key,
             obj.description,
@@ -286,6 +344,107 @@ pub async fn edit_user_description(
     Ok(update_op)
 }
 
+// used.
+pub async fn edit_user_last_seen(
+    username: &str,
+    visibility: &str,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    if visibility != VISIBLE_TO_EVERYONE &&
+       visibility != VISIBLE_TO_CONTACTS &&
+       visibility != VISIBLE_TO_NOBODY
+    {
+        return Err::<(), YokaiErr>(
+            YokaiErr::new("Visibility must be everyone, contacts or nobody.")
+        );
+    }
+    let user: User = match get_user_by_id(
+        username,
+        pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let update_op: () = match query!(
+        "UPDATE users SET last_seen_visibility = $1 WHERE username = $2",
+        visibility,
+        user.username
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(update_op)
+}
+
+// used.
+pub async fn edit_user_read_receipts(
+    username: &str,
+    enabled: &bool,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let user: User = match get_user_by_id(
+        username,
+        pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let update_op: () = match query!(
+        "UPDATE users SET read_receipts = $1 WHERE username = $2",
+        enabled,
+        user.username
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(update_op)
+}
+
+// used.
+pub async fn edit_user_approval(
+    username: &str,
+    enabled: &bool,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let user: User = match get_user_by_id(
+        username,
+        pool
+    ).await {
+        Ok(user) => user,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let update_op: () = match query!(
+        "UPDATE users SET require_approval = $1 WHERE username = $2",
+        enabled,
+        user.username
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(update_op)
+}
+
 // used.
 pub async fn edit_user_primary(
     username: &str,
@@ -431,13 +590,152 @@ pub async fn delete_user(
     Ok(del_op)
 }
 
+// used.
+pub async fn check_outgoing_message(
+    sender: &User,
+    chat: &Chat,
+    attachment: &Option<String>,
+    reply_to: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<Option<String>, YokaiErr>{
+    if is_suspended(sender){
+        return Err::<Option<String>, YokaiErr>(
+            YokaiErr::new("This account is suspended.")
+        );
+    }
+    let participants: Vec<String> = match chat_participants(
+        chat,
+        pool
+    ).await {
+        Ok(participants) => participants,
+        Err(e) => return Err::<Option<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !participants.contains(&sender.username) ||
+       awaiting_approval(chat, &sender.username)
+    {
+        return Err::<Option<String>, YokaiErr>(
+            YokaiErr::new("Sender is not a member of this chat.")
+        );
+    }
+    for participant in &participants {
+        let blocked: bool = match is_blocked_between(
+            &sender.username,
+            participant,
+            pool
+        ).await {
+            Ok(blocked) => blocked,
+            Err(e) => return Err::<Option<String>, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        if blocked {
+            return Err::<Option<String>, YokaiErr>(
+                YokaiErr::new("Messages cannot be sent to this user.")
+            );
+        }
+    }
+    if let Some(file_id) = attachment {
+        let file: UserFile = match get_file_by_id(
+            file_id,
+            pool
+        ).await {
+            Ok(file) => file,
+            Err(_e) => return Err::<Option<String>, YokaiErr>(
+                YokaiErr::new("The file supplied does not exist.")
+            )
+        };
+        if file.file_owner != sender.username &&
+           !file_shared_with(&file.file_id, &sender.username, pool).await
+        {
+            return Err::<Option<String>, YokaiErr>(
+                YokaiErr::new("File ownership could not be verified.")
+            );
+        }
+        if is_quarantined(&file){
+            return Err::<Option<String>, YokaiErr>(
+                YokaiErr::new("File has been quarantined.")
+            );
+        }
+    }
+    match reply_to {
+        Some(parent_id) => {
+            let parent: Message = match get_message_by_id(
+                parent_id,
+                pool
+            ).await {
+                Ok(parent) => parent,
+                Err(e) => return Err::<Option<String>, YokaiErr>(
+                    YokaiErr::new(&e.to_string())
+                )
+            };
+            if parent.chat_id != chat.chat_id || parent.deleted {
+                return Err::<Option<String>, YokaiErr>(
+                    YokaiErr::new("Replies must refer to a message in the same chat.")
+                );
+            }
+            Ok(Some(parent.thread_root.unwrap_or(parent.msg_id)))
+        },
+        None => Ok(None)
+    }
+}
+
 pub async fn create_message(
     msg: &str,
     sender: &str,
     chat_id: &str,
-    receiver: &str,
     attachment: &Option<String>,
+    reply_to: &Option<String>,
     pool: &Pool<Postgres>
+) -> Result<Message, YokaiErr>{
+    let mut tx: Transaction<'_, Postgres> = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let written: Message = match write_message(
+        msg,
+        sender,
+        chat_id,
+        attachment,
+        reply_to,
+        pool,
+        &mut tx
+    ).await {
+        Ok(written) => written,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _commit: () = match tx.commit().await {
+        Ok(_f) => {},
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let fetched: Message = match get_message_by_id(
+        &written.msg_id,
+        pool
+    ).await {
+        Ok(fetched) => fetched,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(fetched)
+}
+
+// used.
+pub async fn write_message(
+    msg: &str,
+    sender: &str,
+    chat_id: &str,
+    attachment: &Option<String>,
+    reply_to: &Option<String>,
+    pool: &Pool<Postgres>,
+    tx: &mut Transaction<'_, Postgres>
 ) -> Result<Message, YokaiErr>{
     if check_message(msg){
         let sender_obj: User = match get_user_by_id(
@@ -449,61 +747,98 @@ pub async fn create_message(
                 YokaiErr::new(&e.to_string())
             )
         };
-        let receiver_obj: User = match get_user_by_id(
-            receiver,
+        let chat: Chat = match get_chat_by_id(
+            chat_id, 
+            pool
+        ).await {
+            Ok(chat) => chat,
+            Err(e) => return Err::<Message, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let thread_root: Option<String> = match check_outgoing_message(
+            &sender_obj,
+            &chat,
+            attachment,
+            reply_to,
+            pool
+        ).await {
+            Ok(thread_root) => thread_root,
+            Err(e) => return Err::<Message, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let participants: Vec<String> = match chat_participants(
+            &chat,
             pool
         ).await {
-            Ok(receiver_obj) => receiver_obj,
+            Ok(participants) => participants,
+            Err(e) => return Err::<Message, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let receiver: Option<String> = if chat.is_group {
+            None
+        }
+        else {
+            participants
+                .iter()
+                .find(|participant| **participant != sender_obj.username)
+                .cloned()
+        };
+        let salt: String = match generate_secret(){
+            Ok(salt) => salt,
             Err(e) => return Err::<Message, YokaiErr>(
                 YokaiErr::new(&e.to_string())
             )
         };
         let id: String = hash_string(
             &format!(
-                "{}{}{}", 
+                "{}{}{}{}", 
                 &sender_obj.username,
-                &receiver_obj.username,
-                &rfc2282()
+                &chat.chat_id,
+                &rfc2282(),
+                &salt
             )
         );
         let encrypted_content: String = match encrypt_message(
-            &sender_obj.public_key,
-            msg
+            msg,
+            &sender_obj.public_key
         ){
             Ok(encrypted_content) => encrypted_content,
             Err(e) => return Err::<Message, YokaiErr>(
                 YokaiErr::new(&e.to_string())
             )
         };
-        let chat: Chat = match get_chat_by_id(
-            chat_id, 
-            pool
-        ).await {
-            Ok(chat) => chat,
-            Err(e) => return Err::<Message, YokaiErr>(
-                YokaiErr::new(&e.to_string())
-            )
-        };
-        let msg: Message = Message{
+        let msg_obj: Message = Message{
             msg_id: id,
             published: rfc2282(),
             content: encrypted_content,
             sender: sender_obj.username,
-            receiver: receiver_obj.username,
+            receiver,
             attachment: attachment.clone(),
-            chat_id: chat.chat_id
+            chat_id: chat.chat_id,
+            edited: None,
+            deleted: false,
+            reply_to: reply_to.clone(),
+            thread_root,
+            expires_at: chat.message_ttl.map(|ttl| rfc2822_after(&ttl)),
+            is_system: false
         };
-        let _insert_op = match query!(
-            "INSERT INTO messages (msg_id, published, content, sender, receiver, attachment, chat_id) VALUES ($1, $2, $3, $4, $5, $6, $7)",
-            msg.msg_id,
-            msg.published,
-            msg.content,
-            msg.sender,
-            msg.receiver,
-            msg.attachment,
-            msg.chat_id,
+        let _insert_op: () = match query!(
+            "INSERT INTO messages (msg_id, published, content, sender, receiver, attachment, chat_id, reply_to, thread_root, expires_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            msg_obj.msg_id,
+            msg_obj.published,
+            msg_obj.content,
+            msg_obj.sender,
+            msg_obj.receiver,
+            msg_obj.attachment,
+            msg_obj.chat_id,
+            msg_obj.reply_to,
+            msg_obj.thread_root,
+            msg_obj.expires_at,
         )
-            .execute(pool)
+            .execute(&mut **tx)
             .await
         {
             Ok(_feedback) => {},
@@ -511,16 +846,20 @@ pub async fn create_message(
                 YokaiErr::new(&e.to_string())
             )
         };
-        let fetched: Message = match get_message_by_id(
-            &msg.msg_id,
-            pool
-        ).await {
-            Ok(fetched) => fetched,
-            Err(e) => return Err::<Message, YokaiErr>(
-                YokaiErr::new(&e.to_string())
-            )
-        };
-        Ok(fetched)
+        for participant in participants {
+            let _key: MessageKey = match create_message_key(
+                &msg_obj.msg_id,
+                &participant,
+                msg,
+                tx
+            ).await {
+                Ok(key) => key,
+                Err(e) => return Err::<Message, YokaiErr>(
+                    YokaiErr::new(&e.to_string())
+                )
+            };
+        }
+        Ok(msg_obj)
     }
     else {
         Err::<Message, YokaiErr>(
@@ -529,166 +868,375 @@ pub async fn create_message(
     }
 }
 
-pub async fn get_message_by_id(
-    message_id: &str,
+// used.
+pub async fn create_system_message(
+    chat_id: &str,
+    actor: &str,
+    text: &str,
     pool: &Pool<Postgres>
 ) -> Result<Message, YokaiErr>{
-    let object: Message = match query_as!(
-        Message,
-        "SELECT * FROM messages WHERE msg_id = $1",
-        message_id
+    let salt: String = match generate_secret(){
+        Ok(salt) => salt,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let id: String = hash_string(
+        &format!(
+            "{}{}{}{}",
+            actor,
+            chat_id,
+            &rfc2282(),
+            &salt
+        )
+    );
+    let msg_obj: Message = Message{
+        msg_id: id,
+        published: rfc2282(),
+        content: text.to_string(),
+        sender: actor.to_string(),
+        receiver: None,
+        attachment: None,
+        chat_id: chat_id.to_string(),
+        edited: None,
+        deleted: false,
+        reply_to: None,
+        thread_root: None,
+        expires_at: None,
+        is_system: true
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO messages (msg_id, published, content, sender, chat_id, is_system) VALUES ($1, $2, $3, $4, $5, $6)",
+        msg_obj.msg_id,
+        msg_obj.published,
+        msg_obj.content,
+        msg_obj.sender,
+        msg_obj.chat_id,
+        msg_obj.is_system,
     )
-        .fetch_one(pool)
-        .await 
+        .execute(pool)
+        .await
     {
-        Ok(object) => object,
+        Ok(_feedback) => {},
         Err(e) => return Err::<Message, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    Ok(object)
+    Ok(msg_obj)
 }
 
-pub async fn delete_message(
-    message_id: &str,
-    pool: &Pool<Postgres>
-) -> Result<(), YokaiErr>{
-    let msg: Message = match get_message_by_id(
-        message_id,
-        pool
-    ).await {
-        Ok(msg) => msg,
-        Err(e) => return Err::<(), YokaiErr>(
+// used.
+pub async fn create_message_key(
+    msg_id: &str,
+    recipient: &str,
+    msg: &str,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<MessageKey, YokaiErr>{
+    let recipient_obj: User = match query_as!(
+        User,
+        "SELECT * FROM users WHERE username = $1",
+        recipient
+    )
+        .fetch_one(&mut **tx)
+        .await
+    {
+        Ok(recipient_obj) => recipient_obj,
+        Err(e) => return Err::<MessageKey, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    let del_op: () = match query!(
-        "DELETE FROM messages WHERE msg_id = $1",
-        msg.msg_id
+    let content: String = match encrypt_message(
+        msg,
+        &recipient_obj.public_key
+    ){
+        Ok(content) => content,
+        Err(e) => return Err::<MessageKey, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let key: MessageKey = MessageKey{
+        msg_id: msg_id.to_string(),
+        recipient: recipient_obj.username,
+        content,
+        delivered_at: None,
+        read_at: None
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO message_keys (msg_id, recipient, content) VALUES ($1, $2, $3)",
+        key.msg_id,
+        key.recipient,
+        key.content,
     )
-        .execute(pool)
-        .await 
+        .execute(&mut **tx)
+        .await
     {
-        Ok(_f) => {},
-        Err(e) => return Err::<(), YokaiErr>(
+        Ok(_feedback) => {},
+        Err(e) => return Err::<MessageKey, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    Ok(del_op)
+    Ok(key)
 }
 
-pub async fn create_chat(
-    sender: &str,
-    receiver: &str,
+// used.
+pub async fn get_message_key(
+    msg_id: &str,
+    recipient: &str,
     pool: &Pool<Postgres>
-) -> Result<Chat, YokaiErr>{
-    let exists: bool = chat_exists(
-        sender,
-        receiver,
+) -> Result<MessageKey, YokaiErr>{
+    let object: MessageKey = match query_as!(
+        MessageKey,
+        "SELECT * FROM message_keys WHERE msg_id = $1 AND recipient = $2",
+        msg_id,
+        recipient
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(object) => object,
+        Err(e) => return Err::<MessageKey, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(object)
+}
+
+pub async fn get_message_by_id(
+    message_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<Message, YokaiErr>{
+    let object: Message = match query_as!(
+        Message,
+        "SELECT * FROM messages WHERE msg_id = $1",
+        message_id
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(object) => object,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(object)
+}
+
+// used.
+pub async fn get_message_keys(
+    msg_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<Vec<MessageKey>, YokaiErr>{
+    let objects: Vec<MessageKey> = match query_as!(
+        MessageKey,
+        "SELECT * FROM message_keys WHERE msg_id = $1",
+        msg_id
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<MessageKey>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn edit_message(
+    msg_id: &str,
+    editor: &str,
+    new_msg: &str,
+    edit_window: &i64,
+    pool: &Pool<Postgres>
+) -> Result<Message, YokaiErr>{
+    if !check_message(new_msg){
+        return Err::<Message, YokaiErr>(
+            YokaiErr::new("Message contains illegal characters.")
+        );
+    }
+    let msg: Message = match get_message_by_id(
+        msg_id,
         pool
-    ).await;
-    if exists {
-        let sender_obj: User = match get_user_by_id(
-            sender,
+    ).await {
+        Ok(msg) => msg,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if msg.sender != editor || msg.deleted || msg.is_system {
+        return Err::<Message, YokaiErr>(
+            YokaiErr::new("Only the sender can edit this message.")
+        );
+    }
+    let age: i64 = match seconds_since(&msg.published){
+        Ok(age) => age,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if age > *edit_window {
+        return Err::<Message, YokaiErr>(
+            YokaiErr::new("The edit window for this message has passed.")
+        );
+    }
+    let sender_obj: User = match get_user_by_id(
+        &msg.sender,
+        pool
+    ).await {
+        Ok(sender_obj) => sender_obj,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let keys: Vec<MessageKey> = match get_message_keys(
+        &msg.msg_id,
+        pool
+    ).await {
+        Ok(keys) => keys,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let edited: String = rfc2282();
+    for key in keys {
+        let recipient_obj: User = match get_user_by_id(
+            &key.recipient,
             pool
         ).await {
-            Ok(sender_obj) => sender_obj,
-            Err(e) => return Err::<Chat, YokaiErr>(
-                YokaiErr::new(&e.to_string())
-            )
+            Ok(recipient_obj) => recipient_obj,
+            Err(_e) => continue
         };
-        let receiver_obj: User = match get_user_by_id(
-            receiver,
-            pool
-        ).await {
-            Ok(receiver_obj) => receiver_obj,
-            Err(e) => return Err::<Chat, YokaiErr>(
+        let content: String = match encrypt_message(
+            new_msg,
+            &recipient_obj.public_key
+        ){
+            Ok(content) => content,
+            Err(e) => return Err::<Message, YokaiErr>(
                 YokaiErr::new(&e.to_string())
             )
         };
-        let id: String = hash_string(
+        let edit_id: String = hash_string(
             &format!(
                 "{}{}{}",
-                &sender_obj.username,
-                &receiver_obj.username,
-                &rfc2282()
+                &key.msg_id,
+                &key.recipient,
+                &key.content
             )
         );
-        let chat: Chat = Chat{
-            chat_id: id,
-            started: rfc2282(),
-            sender: sender_obj.username.clone(),
-            receiver: receiver_obj.username.clone()
-        };
-        let _insert_op = match query!(
-            "INSERT INTO chats (chat_id, started, sender, receiver) VALUES ($1, $2, $3, $4)",
-            chat.chat_id,
-            chat.started,
-            chat.sender,
-            chat.receiver,
+        let _insert_op: () = match query!(
+            "INSERT INTO message_edits (edit_id, msg_id, recipient, content, edited) VALUES ($1, $2, $3, $4, $5)",
+            edit_id,
+            key.msg_id,
+            key.recipient,
+            key.content,
+            edited,
         )
             .execute(pool)
             .await
         {
             Ok(_feedback) => {},
-            Err(e) => return Err::<Chat, YokaiErr>(
+            Err(e) => return Err::<Message, YokaiErr>(
                 YokaiErr::new(&e.to_string())
             )
         };
-        let fetched: Chat = match get_chat_by_id(
-            &chat.chat_id,
-            pool
-        ).await {
-            Ok(fetched) => fetched,
-            Err(e) => return Err::<Chat, YokaiErr>(
+        let _update_op: () = match query!(
+            "UPDATE message_keys SET content = $1 WHERE msg_id = $2 AND recipient = $3",
+            content,
+            key.msg_id,
+            key.recipient
+        )
+            .execute(pool)
+            .await
+        {
+            Ok(_f) => {},
+            Err(e) => return Err::<Message, YokaiErr>(
                 YokaiErr::new(&e.to_string())
             )
         };
-        Ok(fetched)
     }
-    else {
-        Err::<Chat, YokaiErr>(
-            YokaiErr::new("Cannot duplicate chats.")
+    let encrypted_content: String = match encrypt_message(
+        new_msg,
+        &sender_obj.public_key
+    ){
+        Ok(encrypted_content) => encrypted_content,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
         )
-    }
+    };
+    let _update_op: () = match query!(
+        "UPDATE messages SET content = $1, edited = $2 WHERE msg_id = $3",
+        encrypted_content,
+        edited,
+        msg.msg_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let fetched: Message = match get_message_by_id(
+        &msg.msg_id,
+        pool
+    ).await {
+        Ok(fetched) => fetched,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(fetched)
 }
 
-pub async fn get_chat_by_id(
-    chat_id: &str,
+// used.
+pub async fn get_message_edits(
+    msg_id: &str,
+    recipient: &str,
     pool: &Pool<Postgres>
-) -> Result<Chat, YokaiErr>{
-    let object: Chat = match query_as!(
-        Chat,
-        "SELECT * FROM chats WHERE chat_id = $1",
-        chat_id
+) -> Result<Vec<MessageEdit>, YokaiErr>{
+    let mut objects: Vec<MessageEdit> = match query_as!(
+        MessageEdit,
+        "SELECT * FROM message_edits WHERE msg_id = $1 AND recipient = $2",
+        msg_id,
+        recipient
     )
-        .fetch_one(pool)
+        .fetch_all(pool)
         .await 
     {
-        Ok(object) => object,
-        Err(e) => return Err::<Chat, YokaiErr>(
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<MessageEdit>, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    Ok(object)
+    objects.sort_by_key(|edit| -seconds_since(&edit.edited).unwrap_or(0));
+    Ok(objects)
 }
 
-pub async fn delete_chat(
-    chat_id: &str,
+// used.
+pub async fn tombstone_message(
+    msg_id: &str,
+    user: &str,
     pool: &Pool<Postgres>
 ) -> Result<(), YokaiErr>{
-    let chat: Chat = match get_chat_by_id(
-        chat_id,
+    let msg: Message = match get_message_by_id(
+        msg_id,
         pool
     ).await {
-        Ok(chat) => chat,
+        Ok(msg) => msg,
         Err(e) => return Err::<(), YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    let del_op: () = match query!(
-        "DELETE FROM chats WHERE chat_id = $1",
-        chat.chat_id
+    if msg.sender != user || msg.is_system {
+        return Err::<(), YokaiErr>(
+            YokaiErr::new("Only the sender can delete this message.")
+        );
+    }
+    let _del_keys: () = match query!(
+        "DELETE FROM message_keys WHERE msg_id = $1",
+        msg.msg_id
     )
         .execute(pool)
         .await 
@@ -698,87 +1246,3656 @@ pub async fn delete_chat(
             YokaiErr::new(&e.to_string())
         )
     };
-    Ok(del_op)
-}
-
-pub async fn chat_exists(
-    sender: &str,
-    receiver: &str,
-    pool: &Pool<Postgres>
-) -> bool{
-    get_chat_by_participants(
-        sender, 
-        receiver, 
+    let _del_edits: () = match query!(
+        "DELETE FROM message_edits WHERE msg_id = $1",
+        msg.msg_id
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_marks: () = match clear_message_marks(
+        &msg.msg_id,
         pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_reactions: () = match query!(
+        "DELETE FROM message_reactions WHERE msg_id = $1",
+        msg.msg_id
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let update_op: () = match query!(
+        "UPDATE messages SET content = '', attachment = NULL, deleted = true WHERE msg_id = $1",
+        msg.msg_id
     )
+        .execute(pool)
         .await
-        .is_ok()
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(update_op)
 }
 
-pub async fn get_chat_by_participants(
-    sender: &str,
-    receiver: &str,
+pub async fn delete_message(
+    message_id: &str,
     pool: &Pool<Postgres>
-) -> Result<Chat, YokaiErr>{
-    let objects: Vec<Chat> = match query_as!(
-        Chat,
-        "SELECT * FROM chats WHERE sender = $1 AND receiver = $2",
-        sender,
-        receiver
+) -> Result<(), YokaiErr>{
+    let msg: Message = match get_message_by_id(
+        message_id,
+        pool
+    ).await {
+        Ok(msg) => msg,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_keys: () = match query!(
+        "DELETE FROM message_keys WHERE msg_id = $1",
+        msg.msg_id
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_edits: () = match query!(
+        "DELETE FROM message_edits WHERE msg_id = $1",
+        msg.msg_id
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_marks: () = match clear_message_marks(
+        &msg.msg_id,
+        pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_reactions: () = match query!(
+        "DELETE FROM message_reactions WHERE msg_id = $1",
+        msg.msg_id
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let del_op: () = match query!(
+        "DELETE FROM messages WHERE msg_id = $1",
+        msg.msg_id
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(del_op)
+}
+
+pub async fn create_chat(
+    sender: &str,
+    receiver: &str,
+    pool: &Pool<Postgres>
+) -> Result<Chat, YokaiErr>{
+    let exists: bool = chat_exists(
+        sender,
+        receiver,
+        pool
+    ).await;
+    if exists {
+        return Err::<Chat, YokaiErr>(
+            YokaiErr::new("Cannot duplicate chats.")
+        );
+    }
+    let chat: Chat = match open_chat(
+        sender,
+        receiver,
+        pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(chat)
+}
+
+// used.
+pub async fn open_chat(
+    sender: &str,
+    receiver: &str,
+    pool: &Pool<Postgres>
+) -> Result<Chat, YokaiErr>{
+    let sender_obj: User = match get_user_by_id(
+        sender,
+        pool
+    ).await {
+        Ok(sender_obj) => sender_obj,
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let receiver_obj: User = match get_user_by_id(
+        receiver,
+        pool
+    ).await {
+        Ok(receiver_obj) => receiver_obj,
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if sender_obj.username == receiver_obj.username {
+        return Err::<Chat, YokaiErr>(
+            YokaiErr::new("Cannot start a chat with yourself.")
+        );
+    }
+    let blocked: bool = match is_blocked_between(
+        &sender_obj.username,
+        &receiver_obj.username,
+        pool
+    ).await {
+        Ok(blocked) => blocked,
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if blocked {
+        return Err::<Chat, YokaiErr>(
+            YokaiErr::new("This user cannot be contacted.")
+        );
+    }
+    if let Ok(existing) = get_chat_by_participants(
+        &sender_obj.username,
+        &receiver_obj.username,
+        pool
+    ).await {
+        return Ok(existing);
+    }
+    let id: String = hash_string(
+        &format!(
+            "{}{}{}",
+            &sender_obj.username,
+            &receiver_obj.username,
+            &rfc2282()
+        )
+    );
+    let chat: Chat = Chat{
+        chat_id: id,
+        started: rfc2282(),
+        sender: sender_obj.username.clone(),
+        receiver: Some(receiver_obj.username.clone()),
+        is_group: false,
+        title: None,
+        message_ttl: None,
+        pending: receiver_obj.require_approval
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO chats (chat_id, started, sender, receiver, pending) VALUES ($1, $2, $3, $4, $5) ON CONFLICT DO NOTHING",
+        chat.chat_id,
+        chat.started,
+        chat.sender,
+        chat.receiver,
+        chat.pending,
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let fetched: Chat = match get_chat_by_participants(
+        &sender_obj.username,
+        &receiver_obj.username,
+        pool
+    ).await {
+        Ok(fetched) => fetched,
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(fetched)
+}
+
+pub async fn get_chat_by_id(
+    chat_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<Chat, YokaiErr>{
+    let object: Chat = match query_as!(
+        Chat,
+        "SELECT * FROM chats WHERE chat_id = $1",
+        chat_id
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(object) => object,
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(object)
+}
+
+pub async fn delete_chat(
+    chat_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let chat: Chat = match get_chat_by_id(
+        chat_id,
+        pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut tx: Transaction<'_, Postgres> = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _clear: () = match clear_chat(
+        &chat.chat_id,
+        &mut tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    match tx.commit().await {
+        Ok(_f) => Ok(()),
+        Err(e) => Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    }
+}
+
+// used.
+async fn clear_chat(
+    chat_id: &str,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), YokaiErr>{
+    let _del_keys: () = match query!(
+        "DELETE FROM message_keys WHERE msg_id IN (SELECT msg_id FROM messages WHERE chat_id = $1)",
+        chat_id
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_edits: () = match query!(
+        "DELETE FROM message_edits WHERE msg_id IN (SELECT msg_id FROM messages WHERE chat_id = $1)",
+        chat_id
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_reactions: () = match query!(
+        "DELETE FROM message_reactions WHERE msg_id IN (SELECT msg_id FROM messages WHERE chat_id = $1)",
+        chat_id
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_stars: () = match query!(
+        "DELETE FROM starred_messages WHERE msg_id IN (SELECT msg_id FROM messages WHERE chat_id = $1)",
+        chat_id
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_pins: () = match query!(
+        "DELETE FROM pinned_messages WHERE chat_id = $1",
+        chat_id
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_settings: () = match query!(
+        "DELETE FROM chat_settings WHERE chat_id = $1",
+        chat_id
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_scheduled: () = match query!(
+        "DELETE FROM scheduled_messages WHERE chat_id = $1",
+        chat_id
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_members: () = match query!(
+        "DELETE FROM chat_members WHERE chat_id = $1",
+        chat_id
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_messages: () = match query!(
+        "DELETE FROM messages WHERE chat_id = $1",
+        chat_id
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_chat: () = match query!(
+        "DELETE FROM chats WHERE chat_id = $1",
+        chat_id
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+pub async fn chat_exists(
+    sender: &str,
+    receiver: &str,
+    pool: &Pool<Postgres>
+) -> bool{
+    get_chat_by_participants(
+        sender, 
+        receiver, 
+        pool
+    )
+        .await
+        .is_ok()
+}
+
+pub async fn get_chat_by_participants(
+    sender: &str,
+    receiver: &str,
+    pool: &Pool<Postgres>
+) -> Result<Chat, YokaiErr>{
+    let objects: Vec<Chat> = match query_as!(
+        Chat,
+        "SELECT * FROM chats WHERE is_group = false AND ((sender = $1 AND receiver = $2) OR (sender = $2 AND receiver = $1))",
+        sender,
+        receiver
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if objects.len() == 1{
+        Ok(objects[0].clone())
+    }
+    else {
+        Err::<Chat, YokaiErr>(
+            YokaiErr::new("A chat could not be found.")
+        )
+    }
+}
+
+// used.
+pub async fn create_group(
+    owner: &str,
+    title: &str,
+    members: &[String],
+    pool: &Pool<Postgres>
+) -> Result<Chat, YokaiErr>{
+    if title.is_empty() || !check_message(title){
+        return Err::<Chat, YokaiErr>(
+            YokaiErr::new("Group title is invalid.")
+        );
+    }
+    let owner_obj: User = match get_user_by_id(
+        owner,
+        pool
+    ).await {
+        Ok(owner_obj) => owner_obj,
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut pending: Vec<&String> = Vec::new();
+    for member in members {
+        if !user_exists(member, pool).await {
+            return Err::<Chat, YokaiErr>(
+                YokaiErr::new("User does not exist.")
+            );
+        }
+        let blocked: bool = match is_blocked_between(
+            &owner_obj.username,
+            member,
+            pool
+        ).await {
+            Ok(blocked) => blocked,
+            Err(e) => return Err::<Chat, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        if blocked {
+            return Err::<Chat, YokaiErr>(
+                YokaiErr::new("This user cannot be added to this chat.")
+            );
+        }
+        let held: bool = match needs_invite_approval(
+            &owner_obj.username,
+            member,
+            pool
+        ).await {
+            Ok(held) => held,
+            Err(e) => return Err::<Chat, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        if held {
+            pending.push(member);
+        }
+    }
+    let id: String = hash_string(
+        &format!(
+            "{}{}{}",
+            &owner_obj.username,
+            title,
+            &rfc2282()
+        )
+    );
+    let chat: Chat = Chat{
+        chat_id: id,
+        started: rfc2282(),
+        sender: owner_obj.username.clone(),
+        receiver: None,
+        is_group: true,
+        title: Some(title.to_string()),
+        message_ttl: None,
+        pending: false
+    };
+    let mut tx: Transaction<'_, Postgres> = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO chats (chat_id, started, sender, receiver, is_group, title) VALUES ($1, $2, $3, $4, $5, $6)",
+        chat.chat_id,
+        chat.started,
+        chat.sender,
+        chat.receiver,
+        chat.is_group,
+        chat.title,
+    )
+        .execute(&mut *tx)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _owner: ChatMember = match insert_chat_member(
+        &chat.chat_id,
+        &owner_obj.username,
+        OWNER_ROLE,
+        &None,
+        &false,
+        &mut tx
+    ).await {
+        Ok(owner) => owner,
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut added: Vec<&String> = vec![&owner_obj.username];
+    for member in members {
+        if added.contains(&member) {
+            continue;
+        }
+        let _member: ChatMember = match insert_chat_member(
+            &chat.chat_id,
+            member,
+            MEMBER_ROLE,
+            &Some(owner_obj.username.clone()),
+            &pending.contains(&member),
+            &mut tx
+        ).await {
+            Ok(member) => member,
+            Err(e) => return Err::<Chat, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        added.push(member);
+    }
+    let _commit: () = match tx.commit().await {
+        Ok(_f) => {},
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let fetched: Chat = match get_chat_by_id(
+        &chat.chat_id,
+        pool
+    ).await {
+        Ok(fetched) => fetched,
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(fetched)
+}
+
+// used.
+pub async fn add_chat_member(
+    chat_id: &str,
+    inviter: &str,
+    username: &str,
+    role: &str,
+    pool: &Pool<Postgres>
+) -> Result<ChatMember, YokaiErr>{
+    let chat: Chat = match get_chat_by_id(
+        chat_id,
+        pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<ChatMember, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !chat.is_group {
+        return Err::<ChatMember, YokaiErr>(
+            YokaiErr::new("Only group chats have members.")
+        );
+    }
+    let user_obj: User = match get_user_by_id(
+        username,
+        pool
+    ).await {
+        Ok(user_obj) => user_obj,
+        Err(e) => return Err::<ChatMember, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let blocked: bool = match is_blocked_between(
+        inviter,
+        &user_obj.username,
+        pool
+    ).await {
+        Ok(blocked) => blocked,
+        Err(e) => return Err::<ChatMember, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if blocked {
+        return Err::<ChatMember, YokaiErr>(
+            YokaiErr::new("This user cannot be added to this chat.")
+        );
+    }
+    let held: bool = match needs_invite_approval(
+        inviter,
+        &user_obj.username,
+        pool
+    ).await {
+        Ok(held) => held,
+        Err(e) => return Err::<ChatMember, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut tx: Transaction<'_, Postgres> = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<ChatMember, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let member: ChatMember = match insert_chat_member(
+        &chat.chat_id,
+        &user_obj.username,
+        role,
+        &Some(inviter.to_string()),
+        &held,
+        &mut tx
+    ).await {
+        Ok(member) => member,
+        Err(e) => return Err::<ChatMember, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _commit: () = match tx.commit().await {
+        Ok(_f) => {},
+        Err(e) => return Err::<ChatMember, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(member)
+}
+
+// used.
+pub async fn needs_invite_approval(
+    inviter: &str,
+    invitee: &str,
+    pool: &Pool<Postgres>
+) -> Result<bool, YokaiErr>{
+    let invitee_obj: User = match get_user_by_id(
+        invitee,
+        pool
+    ).await {
+        Ok(invitee_obj) => invitee_obj,
+        Err(e) => return Err::<bool, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !invitee_obj.require_approval {
+        return Ok(false);
+    }
+    let known: Option<bool> = match query_scalar!(
+        "SELECT EXISTS (SELECT 1 FROM chats WHERE is_group = false AND ((sender = $1 AND receiver = $2) OR (sender = $2 AND receiver = $1 AND pending = false))) OR EXISTS (SELECT 1 FROM chat_members invitee JOIN chat_members inviter ON invitee.chat_id = inviter.chat_id WHERE invitee.username = $1 AND inviter.username = $2 AND invitee.pending = false AND inviter.pending = false)",
+        invitee_obj.username,
+        inviter
+    )
+        .fetch_one(pool)
+        .await
+    {
+        Ok(known) => known,
+        Err(e) => return Err::<bool, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(!known.unwrap_or(false))
+}
+
+// used.
+async fn insert_chat_member(
+    chat_id: &str,
+    username: &str,
+    role: &str,
+    invited_by: &Option<String>,
+    pending: &bool,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<ChatMember, YokaiErr>{
+    let member: ChatMember = ChatMember{
+        chat_id: chat_id.to_string(),
+        username: username.to_string(),
+        member_role: role.to_string(),
+        joined: rfc2282(),
+        pending: *pending,
+        invited_by: invited_by.clone()
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO chat_members (chat_id, username, member_role, joined, pending, invited_by) VALUES ($1, $2, $3, $4, $5, $6)",
+        member.chat_id,
+        member.username,
+        member.member_role,
+        member.joined,
+        member.pending,
+        member.invited_by,
+    )
+        .execute(&mut **tx)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<ChatMember, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(member)
+}
+
+// used.
+pub async fn get_chat_member(
+    chat_id: &str,
+    username: &str,
+    pool: &Pool<Postgres>
+) -> Result<ChatMember, YokaiErr>{
+    let object: ChatMember = match query_as!(
+        ChatMember,
+        "SELECT * FROM chat_members WHERE chat_id = $1 AND username = $2 AND pending = false",
+        chat_id,
+        username
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(object) => object,
+        Err(e) => return Err::<ChatMember, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(object)
+}
+
+// used.
+pub async fn get_chat_members(
+    chat_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<Vec<ChatMember>, YokaiErr>{
+    let objects: Vec<ChatMember> = match query_as!(
+        ChatMember,
+        "SELECT * FROM chat_members WHERE chat_id = $1 AND pending = false",
+        chat_id
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<ChatMember>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn is_chat_member(
+    chat_id: &str,
+    username: &str,
+    pool: &Pool<Postgres>
+) -> bool {
+    get_chat_member(
+        chat_id,
+        username,
+        pool
+    ).await.is_ok()
+}
+
+// used.
+pub async fn set_member_role(
+    chat_id: &str,
+    username: &str,
+    role: &str,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let member: ChatMember = match get_chat_member(
+        chat_id,
+        username,
+        pool
+    ).await {
+        Ok(member) => member,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let update_op: () = match query!(
+        "UPDATE chat_members SET member_role = $1 WHERE chat_id = $2 AND username = $3",
+        role,
+        member.chat_id,
+        member.username
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(update_op)
+}
+
+// used.
+pub async fn remove_chat_member(
+    chat_id: &str,
+    username: &str,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let mut tx: Transaction<'_, Postgres> = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _leave: () = match leave_chat(
+        chat_id,
+        username,
+        &mut tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    match tx.commit().await {
+        Ok(_f) => Ok(()),
+        Err(e) => Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    }
+}
+
+// used.
+async fn leave_chat(
+    chat_id: &str,
+    username: &str,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), YokaiErr>{
+    let member: ChatMember = match query_as!(
+        ChatMember,
+        "SELECT * FROM chat_members WHERE chat_id = $1 AND username = $2 AND pending = false",
+        chat_id,
+        username
+    )
+        .fetch_one(&mut **tx)
+        .await
+    {
+        Ok(member) => member,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_op: () = match query!(
+        "DELETE FROM chat_members WHERE chat_id = $1 AND username = $2",
+        member.chat_id,
+        member.username
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_settings: () = match query!(
+        "DELETE FROM chat_settings WHERE chat_id = $1 AND username = $2",
+        member.chat_id,
+        member.username
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_stars: () = match query!(
+        "DELETE FROM starred_messages WHERE username = $1 AND msg_id IN (SELECT msg_id FROM messages WHERE chat_id = $2)",
+        member.username,
+        member.chat_id
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_keys: () = match query!(
+        "DELETE FROM message_keys WHERE recipient = $1 AND msg_id IN (SELECT msg_id FROM messages WHERE chat_id = $2)",
+        member.username,
+        member.chat_id
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let remaining: Vec<ChatMember> = match query_as!(
+        ChatMember,
+        "SELECT * FROM chat_members WHERE chat_id = $1 AND pending = false",
+        member.chat_id
+    )
+        .fetch_all(&mut **tx)
+        .await
+    {
+        Ok(remaining) => remaining,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if remaining.is_empty(){
+        return clear_chat(&member.chat_id, tx).await;
+    }
+    if member.member_role == OWNER_ROLE {
+        let successor: &ChatMember = match remaining
+            .iter()
+            .find(|other| other.member_role == ADMIN_ROLE)
+        {
+            Some(successor) => successor,
+            None => &remaining[0]
+        };
+        let _promote_op: () = match query!(
+            "UPDATE chat_members SET member_role = $1 WHERE chat_id = $2 AND username = $3",
+            OWNER_ROLE,
+            successor.chat_id,
+            successor.username
+        )
+            .execute(&mut **tx)
+            .await
+        {
+            Ok(_f) => {},
+            Err(e) => return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+    }
+    Ok(())
+}
+
+// used.
+pub async fn chat_participants(
+    chat: &Chat,
+    pool: &Pool<Postgres>
+) -> Result<Vec<String>, YokaiErr>{
+    if chat.is_group {
+        let members: Vec<ChatMember> = match get_chat_members(
+            &chat.chat_id,
+            pool
+        ).await {
+            Ok(members) => members,
+            Err(e) => return Err::<Vec<String>, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        Ok(
+            members
+                .into_iter()
+                .map(|member| member.username)
+                .collect::<Vec<String>>()
+        )
+    }
+    else {
+        let mut participants: Vec<String> = vec![chat.sender.clone()];
+        if let Some(receiver) = &chat.receiver {
+            participants.push(receiver.clone());
+        }
+        Ok(participants)
+    }
+}
+
+// used.
+pub fn awaiting_approval(
+    chat: &Chat,
+    user: &str
+) -> bool {
+    chat.pending && chat.receiver.as_deref() == Some(user)
+}
+
+// used.
+pub async fn active_participants(
+    chat: &Chat,
+    pool: &Pool<Postgres>
+) -> Result<Vec<String>, YokaiErr>{
+    let participants: Vec<String> = match chat_participants(
+        chat,
+        pool
+    ).await {
+        Ok(participants) => participants,
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(
+        participants
+            .into_iter()
+            .filter(|participant| !awaiting_approval(chat, participant))
+            .collect::<Vec<String>>()
+    )
+}
+
+// used.
+pub async fn is_chat_participant(
+    chat: &Chat,
+    user: &str,
+    pool: &Pool<Postgres>
+) -> bool {
+    match active_participants(chat, pool).await {
+        Ok(participants) => participants.iter().any(|p| p == user),
+        Err(_e) => false
+    }
+}
+
+// used.
+pub async fn set_chat_timer(
+    chat_id: &str,
+    actor: &str,
+    ttl: &Option<i64>,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    if let Some(seconds) = ttl && !MESSAGE_TTLS.contains(seconds){
+        return Err::<(), YokaiErr>(
+            YokaiErr::new("Unsupported disappearing message timer.")
+        );
+    }
+    let chat: Chat = match get_chat_by_id(
+        chat_id,
+        pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _update_op: () = match query!(
+        "UPDATE chats SET message_ttl = $1 WHERE chat_id = $2",
+        *ttl,
+        chat.chat_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let text: String = match ttl {
+        Some(seconds) => format!(
+            "{} set messages to disappear after {} seconds.",
+            actor,
+            seconds
+        ),
+        None => format!("{} turned off disappearing messages.", actor)
+    };
+    let _system: Message = match create_system_message(
+        &chat.chat_id,
+        actor,
+        &text,
+        pool
+    ).await {
+        Ok(system) => system,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn get_expiring_messages(
+    pool: &Pool<Postgres>
+) -> Result<Vec<Message>, YokaiErr>{
+    let objects: Vec<Message> = match query_as!(
+        Message,
+        "SELECT * FROM messages WHERE expires_at IS NOT NULL"
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<Message>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn create_scheduled_message(
+    msg: &str,
+    sender: &str,
+    chat_id: &str,
+    attachment: &Option<String>,
+    reply_to: &Option<String>,
+    send_at: &str,
+    pool: &Pool<Postgres>
+) -> Result<ScheduledMessage, YokaiErr>{
+    if !check_message(msg){
+        return Err::<ScheduledMessage, YokaiErr>(
+            YokaiErr::new("Message contains invalid characters.")
+        );
+    }
+    let pending: i64 = match seconds_since(send_at){
+        Ok(pending) => pending,
+        Err(e) => return Err::<ScheduledMessage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if pending >= 0 {
+        return Err::<ScheduledMessage, YokaiErr>(
+            YokaiErr::new("Scheduled messages must be sent in the future.")
+        );
+    }
+    let sender_obj: User = match get_user_by_id(
+        sender,
+        pool
+    ).await {
+        Ok(sender_obj) => sender_obj,
+        Err(e) => return Err::<ScheduledMessage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match get_chat_by_id(
+        chat_id,
+        pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<ScheduledMessage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _thread_root: Option<String> = match check_outgoing_message(
+        &sender_obj,
+        &chat,
+        attachment,
+        reply_to,
+        pool
+    ).await {
+        Ok(thread_root) => thread_root,
+        Err(e) => return Err::<ScheduledMessage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let content: String = match encrypt_message(
+        msg,
+        &sender_obj.public_key
+    ){
+        Ok(content) => content,
+        Err(e) => return Err::<ScheduledMessage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let salt: String = match generate_secret(){
+        Ok(salt) => salt,
+        Err(e) => return Err::<ScheduledMessage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let id: String = hash_string(
+        &format!(
+            "{}{}{}{}",
+            sender,
+            chat_id,
+            &rfc2282(),
+            &salt
+        )
+    );
+    let scheduled: ScheduledMessage = ScheduledMessage{
+        schedule_id: id,
+        chat_id: chat.chat_id,
+        sender: sender_obj.username,
+        content,
+        attachment: attachment.clone(),
+        reply_to: reply_to.clone(),
+        send_at: send_at.to_string(),
+        created: rfc2282(),
+        attempts: 0,
+        last_error: None,
+        failed: false
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO scheduled_messages (schedule_id, chat_id, sender, content, attachment, reply_to, send_at, created) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        scheduled.schedule_id,
+        scheduled.chat_id,
+        scheduled.sender,
+        scheduled.content,
+        scheduled.attachment,
+        scheduled.reply_to,
+        scheduled.send_at,
+        scheduled.created,
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<ScheduledMessage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(scheduled)
+}
+
+// used.
+pub async fn get_scheduled_message(
+    schedule_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<ScheduledMessage, YokaiErr>{
+    let object: ScheduledMessage = match query_as!(
+        ScheduledMessage,
+        "SELECT * FROM scheduled_messages WHERE schedule_id = $1",
+        schedule_id
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(object) => object,
+        Err(e) => return Err::<ScheduledMessage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(object)
+}
+
+// used.
+pub async fn get_scheduled_messages(
+    pool: &Pool<Postgres>
+) -> Result<Vec<ScheduledMessage>, YokaiErr>{
+    let objects: Vec<ScheduledMessage> = match query_as!(
+        ScheduledMessage,
+        "SELECT * FROM scheduled_messages WHERE failed = false"
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<ScheduledMessage>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn get_user_scheduled_messages(
+    sender: &str,
+    pool: &Pool<Postgres>
+) -> Result<Vec<ScheduledMessage>, YokaiErr>{
+    let objects: Vec<ScheduledMessage> = match query_as!(
+        ScheduledMessage,
+        "SELECT * FROM scheduled_messages WHERE sender = $1",
+        sender
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<ScheduledMessage>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn delete_scheduled_message(
+    schedule_id: &str,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), YokaiErr>{
+    let _del_op: () = match query!(
+        "DELETE FROM scheduled_messages WHERE schedule_id = $1",
+        schedule_id
+    )
+        .execute(&mut **tx)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn claim_scheduled_message(
+    schedule_id: &str,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<Option<ScheduledMessage>, YokaiErr>{
+    let object: Option<ScheduledMessage> = match query_as!(
+        ScheduledMessage,
+        "SELECT * FROM scheduled_messages WHERE schedule_id = $1 AND failed = false FOR UPDATE SKIP LOCKED",
+        schedule_id
+    )
+        .fetch_optional(&mut **tx)
+        .await 
+    {
+        Ok(object) => object,
+        Err(e) => return Err::<Option<ScheduledMessage>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(object)
+}
+
+// used.
+pub async fn record_delivery_failure(
+    schedule_id: &str,
+    error: &str,
+    failed: &bool,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), YokaiErr>{
+    let _update_op: () = match query!(
+        "UPDATE scheduled_messages SET attempts = attempts + 1, last_error = $1, failed = $2 WHERE schedule_id = $3",
+        error,
+        failed,
+        schedule_id
+    )
+        .execute(&mut **tx)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn cancel_scheduled_message(
+    schedule_id: &str,
+    sender: &str,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let scheduled: ScheduledMessage = match get_scheduled_message(
+        schedule_id,
+        pool
+    ).await {
+        Ok(scheduled) => scheduled,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if scheduled.sender != sender {
+        return Err::<(), YokaiErr>(
+            YokaiErr::new("Only the sender can cancel this message.")
+        );
+    }
+    let mut tx: Transaction<'_, Postgres> = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_op: () = match delete_scheduled_message(
+        &scheduled.schedule_id,
+        &mut tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    match tx.commit().await {
+        Ok(_f) => Ok(()),
+        Err(e) => Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    }
+}
+
+// used.
+pub async fn clear_message_marks(
+    msg_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let _del_pins: () = match query!(
+        "DELETE FROM pinned_messages WHERE msg_id = $1",
+        msg_id
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_stars: () = match query!(
+        "DELETE FROM starred_messages WHERE msg_id = $1",
+        msg_id
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn visible_message(
+    msg_id: &str,
+    user: &str,
+    pool: &Pool<Postgres>
+) -> Result<Message, YokaiErr>{
+    let msg: Message = match get_message_by_id(
+        msg_id,
+        pool
+    ).await {
+        Ok(msg) => msg,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match get_chat_by_id(
+        &msg.chat_id,
+        pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !is_chat_participant(&chat, user, pool).await {
+        return Err::<Message, YokaiErr>(
+            YokaiErr::new("User is not a member of this chat.")
+        );
+    }
+    if msg.deleted {
+        return Err::<Message, YokaiErr>(
+            YokaiErr::new("This message has been deleted.")
+        );
+    }
+    Ok(msg)
+}
+
+// used.
+pub async fn pin_message(
+    msg_id: &str,
+    user: &str,
+    pool: &Pool<Postgres>
+) -> Result<PinnedMessage, YokaiErr>{
+    let msg: Message = match visible_message(
+        msg_id,
+        user,
+        pool
+    ).await {
+        Ok(msg) => msg,
+        Err(e) => return Err::<PinnedMessage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let pin: PinnedMessage = PinnedMessage{
+        chat_id: msg.chat_id,
+        msg_id: msg.msg_id,
+        pinned_by: user.to_string(),
+        pinned: rfc2282()
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO pinned_messages (chat_id, msg_id, pinned_by, pinned) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+        pin.chat_id,
+        pin.msg_id,
+        pin.pinned_by,
+        pin.pinned,
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<PinnedMessage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(pin)
+}
+
+// used.
+pub async fn unpin_message(
+    msg_id: &str,
+    user: &str,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let msg: Message = match get_message_by_id(
+        msg_id,
+        pool
+    ).await {
+        Ok(msg) => msg,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match get_chat_by_id(
+        &msg.chat_id,
+        pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !is_chat_participant(&chat, user, pool).await {
+        return Err::<(), YokaiErr>(
+            YokaiErr::new("User is not a member of this chat.")
+        );
+    }
+    let _del_op: () = match query!(
+        "DELETE FROM pinned_messages WHERE chat_id = $1 AND msg_id = $2",
+        chat.chat_id,
+        msg.msg_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn get_chat_pins(
+    chat_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<Vec<PinnedMessage>, YokaiErr>{
+    let objects: Vec<PinnedMessage> = match query_as!(
+        PinnedMessage,
+        "SELECT * FROM pinned_messages WHERE chat_id = $1",
+        chat_id
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<PinnedMessage>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn star_message(
+    msg_id: &str,
+    user: &str,
+    pool: &Pool<Postgres>
+) -> Result<StarredMessage, YokaiErr>{
+    let msg: Message = match visible_message(
+        msg_id,
+        user,
+        pool
+    ).await {
+        Ok(msg) => msg,
+        Err(e) => return Err::<StarredMessage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let star: StarredMessage = StarredMessage{
+        username: user.to_string(),
+        msg_id: msg.msg_id,
+        starred: rfc2282()
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO starred_messages (username, msg_id, starred) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+        star.username,
+        star.msg_id,
+        star.starred,
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<StarredMessage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(star)
+}
+
+// used.
+pub async fn unstar_message(
+    msg_id: &str,
+    user: &str,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let _del_op: () = match query!(
+        "DELETE FROM starred_messages WHERE username = $1 AND msg_id = $2",
+        user,
+        msg_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn get_user_stars(
+    user: &str,
+    pool: &Pool<Postgres>
+) -> Result<Vec<StarredMessage>, YokaiErr>{
+    let objects: Vec<StarredMessage> = match query_as!(
+        StarredMessage,
+        "SELECT * FROM starred_messages WHERE username = $1",
+        user
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<StarredMessage>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn get_member_chat(
+    chat_id: &str,
+    user: &str,
+    pool: &Pool<Postgres>
+) -> Result<Chat, YokaiErr>{
+    let chat: Chat = match get_chat_by_id(
+        chat_id,
+        pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if !is_chat_participant(&chat, user, pool).await {
+        return Err::<Chat, YokaiErr>(
+            YokaiErr::new("User is not a member of this chat.")
+        );
+    }
+    Ok(chat)
+}
+
+// used.
+pub async fn get_user_chat_settings(
+    user: &str,
+    pool: &Pool<Postgres>
+) -> Result<HashMap<String, ChatSetting>, YokaiErr>{
+    let objects: Vec<ChatSetting> = match query_as!(
+        ChatSetting,
+        "SELECT * FROM chat_settings WHERE username = $1",
+        user
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<HashMap<String, ChatSetting>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let settings: HashMap<String, ChatSetting> = objects
+        .into_iter()
+        .map(|setting| (setting.chat_id.clone(), setting))
+        .collect::<HashMap<String, ChatSetting>>();
+    Ok(settings)
+}
+
+// used.
+pub async fn set_chat_archived(
+    chat_id: &str,
+    user: &str,
+    archived: &bool,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let _upsert_op: () = match query!(
+        "INSERT INTO chat_settings (chat_id, username, archived) VALUES ($1, $2, $3) ON CONFLICT (chat_id, username) DO UPDATE SET archived = EXCLUDED.archived",
+        chat_id,
+        user,
+        *archived
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn set_chat_pinned(
+    chat_id: &str,
+    user: &str,
+    pinned: &bool,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let _upsert_op: () = match query!(
+        "INSERT INTO chat_settings (chat_id, username, pinned) VALUES ($1, $2, $3) ON CONFLICT (chat_id, username) DO UPDATE SET pinned = EXCLUDED.pinned",
+        chat_id,
+        user,
+        *pinned
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn set_chat_muted(
+    chat_id: &str,
+    user: &str,
+    muted_until: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    if let Some(until) = muted_until &&
+       let Err(e) = seconds_since(until)
+    {
+        return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        );
+    }
+    let _upsert_op: () = match query!(
+        "INSERT INTO chat_settings (chat_id, username, muted_until) VALUES ($1, $2, $3) ON CONFLICT (chat_id, username) DO UPDATE SET muted_until = EXCLUDED.muted_until",
+        chat_id,
+        user,
+        *muted_until
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn unmuted_users(
+    chat_id: &str,
+    users: &[String],
+    pool: &Pool<Postgres>
+) -> Result<Vec<String>, YokaiErr>{
+    let muted: Vec<String> = match query_scalar!(
+        "SELECT username FROM chat_settings WHERE chat_id = $1 AND username = ANY($2) AND muted_until IS NOT NULL AND muted_until::timestamptz > now()",
+        chat_id,
+        users
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(muted) => muted,
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let unmuted: Vec<String> = users
+        .iter()
+        .filter(|user| !muted.contains(user))
+        .cloned()
+        .collect::<Vec<String>>();
+    Ok(unmuted)
+}
+
+// used.
+pub async fn set_chat_nickname(
+    chat_id: &str,
+    user: &str,
+    nickname: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    if let Some(name) = nickname &&
+       (name.trim().is_empty() || name.chars().count() > MAX_NICKNAME_LENGTH)
+    {
+        return Err::<(), YokaiErr>(
+            YokaiErr::new(
+                &format!(
+                    "Nickname must be between 1 and {} characters.",
+                    MAX_NICKNAME_LENGTH
+                )
+            )
+        );
+    }
+    let _upsert_op: () = match query!(
+        "INSERT INTO chat_settings (chat_id, username, nickname) VALUES ($1, $2, $3) ON CONFLICT (chat_id, username) DO UPDATE SET nickname = EXCLUDED.nickname",
+        chat_id,
+        user,
+        *nickname
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn block_user(
+    blocker: &str,
+    blocked: &str,
+    pool: &Pool<Postgres>
+) -> Result<UserBlock, YokaiErr>{
+    let blocked_obj: User = match get_user_by_id(
+        blocked,
+        pool
+    ).await {
+        Ok(blocked_obj) => blocked_obj,
+        Err(e) => return Err::<UserBlock, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if blocked_obj.username == blocker {
+        return Err::<UserBlock, YokaiErr>(
+            YokaiErr::new("Cannot block yourself.")
+        );
+    }
+    let block: UserBlock = UserBlock{
+        blocker: blocker.to_string(),
+        blocked: blocked_obj.username,
+        created: rfc2282()
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO user_blocks (blocker, blocked, created) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+        block.blocker,
+        block.blocked,
+        block.created,
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<UserBlock, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(block)
+}
+
+// used.
+pub async fn unblock_user(
+    blocker: &str,
+    blocked: &str,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let _del_op: () = match query!(
+        "DELETE FROM user_blocks WHERE blocker = $1 AND blocked = $2",
+        blocker,
+        blocked
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn get_user_blocks(
+    blocker: &str,
+    pool: &Pool<Postgres>
+) -> Result<Vec<UserBlock>, YokaiErr>{
+    let objects: Vec<UserBlock> = match query_as!(
+        UserBlock,
+        "SELECT * FROM user_blocks WHERE blocker = $1",
+        blocker
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<UserBlock>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn is_blocked_between(
+    first: &str,
+    second: &str,
+    pool: &Pool<Postgres>
+) -> Result<bool, YokaiErr> {
+    let objects: Vec<UserBlock> = match query_as!(
+        UserBlock,
+        "SELECT * FROM user_blocks WHERE (blocker = $1 AND blocked = $2) OR (blocker = $2 AND blocked = $1)",
+        first,
+        second
+    )
+        .fetch_all(pool)
+        .await
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<bool, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(!objects.is_empty())
+}
+
+// used.
+pub async fn get_chat_requests(
+    user: &str,
+    pool: &Pool<Postgres>
+) -> Result<Vec<Chat>, YokaiErr>{
+    let objects: Vec<Chat> = match query_as!(
+        Chat,
+        "SELECT * FROM chats WHERE (receiver = $1 AND pending = true) OR chat_id IN (SELECT chat_id FROM chat_members WHERE username = $1 AND pending = true)",
+        user
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<Chat>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn get_chat_request(
+    chat_id: &str,
+    user: &str,
+    pool: &Pool<Postgres>
+) -> Result<Chat, YokaiErr>{
+    let chat: Chat = match get_chat_by_id(
+        chat_id,
+        pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<Chat, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let invited: bool = if chat.is_group {
+        match query_scalar!(
+            "SELECT EXISTS (SELECT 1 FROM chat_members WHERE chat_id = $1 AND username = $2 AND pending = true)",
+            chat.chat_id,
+            user
+        )
+            .fetch_one(pool)
+            .await
+        {
+            Ok(invited) => invited.unwrap_or(false),
+            Err(e) => return Err::<Chat, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        }
+    }
+    else {
+        awaiting_approval(&chat, user)
+    };
+    if !invited {
+        return Err::<Chat, YokaiErr>(
+            YokaiErr::new("No pending request for this chat.")
+        );
+    }
+    Ok(chat)
+}
+
+// used.
+pub async fn accept_chat_request(
+    chat_id: &str,
+    user: &str,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let chat: Chat = match get_chat_request(
+        chat_id,
+        user,
+        pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if chat.is_group {
+        let _update_op: () = match query!(
+            "UPDATE chat_members SET pending = false, joined = $3 WHERE chat_id = $1 AND username = $2 AND pending = true",
+            chat.chat_id,
+            user,
+            rfc2282()
+        )
+            .execute(pool)
+            .await
+        {
+            Ok(_f) => {},
+            Err(e) => return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        return Ok(());
+    }
+    let _update_op: () = match query!(
+        "UPDATE chats SET pending = false WHERE chat_id = $1",
+        chat.chat_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn decline_chat_request(
+    chat_id: &str,
+    user: &str,
+    block: &bool,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let chat: Chat = match get_chat_request(
+        chat_id,
+        user,
+        pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if chat.is_group {
+        let inviter: Option<String> = match query_scalar!(
+            "DELETE FROM chat_members WHERE chat_id = $1 AND username = $2 AND pending = true RETURNING invited_by",
+            chat.chat_id,
+            user
+        )
+            .fetch_one(pool)
+            .await
+        {
+            Ok(inviter) => inviter,
+            Err(e) => return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        if *block && let Some(inviter) = inviter {
+            let _block: UserBlock = match block_user(
+                user,
+                &inviter,
+                pool
+            ).await {
+                Ok(block) => block,
+                Err(e) => return Err::<(), YokaiErr>(
+                    YokaiErr::new(&e.to_string())
+                )
+            };
+        }
+        return Ok(());
+    }
+    if *block {
+        let _block: UserBlock = match block_user(
+            user,
+            &chat.sender,
+            pool
+        ).await {
+            Ok(block) => block,
+            Err(e) => return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+    }
+    let _del_chat: () = match delete_chat(
+        &chat.chat_id,
+        pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn create_report(
+    reporter: &str,
+    reported_user: &str,
+    msg_id: &Option<String>,
+    reason: &str,
+    disclosed: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<Report, YokaiErr>{
+    if reason.trim().is_empty() || !check_message(reason){
+        return Err::<Report, YokaiErr>(
+            YokaiErr::new(
+                &format!(
+                    "A report needs a reason of at most {} bytes.",
+                    MAX_MESSAGE_BYTES
+                )
+            )
+        );
+    }
+    let reported_obj: User = match get_user_by_id(
+        reported_user,
+        pool
+    ).await {
+        Ok(reported_obj) => reported_obj,
+        Err(e) => return Err::<Report, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if reported_obj.username == reporter {
+        return Err::<Report, YokaiErr>(
+            YokaiErr::new("Cannot report yourself.")
+        );
+    }
+    let open_reports: Option<i64> = match query_scalar!(
+        "SELECT COUNT(*) FROM reports WHERE reporter = $1 AND reported_user = $2 AND msg_id IS NOT DISTINCT FROM $3 AND status = $4",
+        reporter,
+        reported_obj.username,
+        *msg_id,
+        REPORT_OPEN
+    )
+        .fetch_one(pool)
+        .await
+    {
+        Ok(open_reports) => open_reports,
+        Err(e) => return Err::<Report, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if open_reports.unwrap_or(0) > 0 {
+        return Err::<Report, YokaiErr>(
+            YokaiErr::new("This has already been reported and is awaiting review.")
+        );
+    }
+    let salt: String = match generate_secret(){
+        Ok(salt) => salt,
+        Err(e) => return Err::<Report, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let report: Report = Report{
+        report_id: hash_string(
+            &format!(
+                "{}{}{}{}",
+                reporter,
+                &reported_obj.username,
+                &rfc2282(),
+                &salt
+            )
+        ),
+        reporter: reporter.to_string(),
+        reported_user: reported_obj.username,
+        msg_id: msg_id.clone(),
+        reason: reason.to_string(),
+        disclosed: disclosed.clone(),
+        status: REPORT_OPEN.to_string(),
+        created: rfc2282(),
+        resolved: None,
+        resolved_by: None
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO reports (report_id, reporter, reported_user, msg_id, reason, disclosed, status, created) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        report.report_id,
+        report.reporter,
+        report.reported_user,
+        report.msg_id,
+        report.reason,
+        report.disclosed,
+        report.status,
+        report.created,
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<Report, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(report)
+}
+
+// used.
+pub async fn report_message(
+    reporter: &User,
+    msg_id: &str,
+    reason: &str,
+    disclose: &bool,
+    pool: &Pool<Postgres>
+) -> Result<Report, YokaiErr>{
+    let msg: Message = match visible_message(
+        msg_id,
+        &reporter.username,
+        pool
+    ).await {
+        Ok(msg) => msg,
+        Err(e) => return Err::<Report, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let disclosed: Option<String> = if *disclose {
+        let key: MessageKey = match get_message_key(
+            &msg.msg_id,
+            &reporter.username,
+            pool
+        ).await {
+            Ok(key) => key,
+            Err(e) => return Err::<Report, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        match decrypt_message(&key.content, &reporter.private_key){
+            Ok(plaintext) => Some(plaintext),
+            Err(e) => return Err::<Report, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        }
+    }
+    else {
+        None
+    };
+    let report: Report = match create_report(
+        &reporter.username,
+        &msg.sender,
+        &Some(msg.msg_id),
+        reason,
+        &disclosed,
+        pool
+    ).await {
+        Ok(report) => report,
+        Err(e) => return Err::<Report, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(report)
+}
+
+// used.
+pub async fn get_report(
+    report_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<Report, YokaiErr>{
+    let object: Report = match query_as!(
+        Report,
+        "SELECT * FROM reports WHERE report_id = $1",
+        report_id
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(object) => object,
+        Err(e) => return Err::<Report, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(object)
+}
+
+// used.
+pub async fn count_reports(
+    status: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<i64, YokaiErr>{
+    let count: Option<i64> = match query_scalar!(
+        "SELECT COUNT(*) FROM reports WHERE $1::text IS NULL OR status = $1",
+        *status
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(count) => count,
+        Err(e) => return Err::<i64, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(count.unwrap_or(0))
+}
+
+// used.
+pub async fn get_reports(
+    status: &Option<String>,
+    limit: &i64,
+    offset: &i64,
+    pool: &Pool<Postgres>
+) -> Result<Vec<Report>, YokaiErr>{
+    let objects: Vec<Report> = match query_as!(
+        Report,
+        "SELECT * FROM reports WHERE $1::text IS NULL OR status = $1 ORDER BY created::timestamptz, report_id LIMIT $2 OFFSET $3",
+        *status,
+        limit,
+        offset
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<Report>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn resolve_report(
+    report_id: &str,
+    admin: &str,
+    action: &str,
+    note: &Option<String>,
+    ip: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let report: Report = match get_report(
+        report_id,
+        pool
+    ).await {
+        Ok(report) => report,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if report.status != REPORT_OPEN {
+        return Err::<(), YokaiErr>(
+            YokaiErr::new("This report has already been handled.")
+        );
+    }
+    let mut tx: Transaction<'_, Postgres> = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut purged: Vec<String> = Vec::new();
+    let status: &str = match action {
+        ACTION_DISMISS => REPORT_DISMISSED,
+        ACTION_DELETE_MESSAGE => {
+            let msg_id: String = match &report.msg_id {
+                Some(msg_id) => msg_id.clone(),
+                None => return Err::<(), YokaiErr>(
+                    YokaiErr::new("This report does not refer to a message.")
+                )
+            };
+            let _del_op: () = match delete_message(
+                &msg_id,
+                pool
+            ).await {
+                Ok(_f) => {},
+                Err(e) => return Err::<(), YokaiErr>(
+                    YokaiErr::new(&e.to_string())
+                )
+            };
+            REPORT_RESOLVED
+        },
+        ACTION_SUSPEND => {
+            let reason: String = match note {
+                Some(note) => note.clone(),
+                None => report.reason.clone()
+            };
+            let _suspend_op: () = match suspend_user(
+                admin,
+                &report.reported_user,
+                &reason,
+                &None,
+                &mut tx
+            ).await {
+                Ok(_f) => {},
+                Err(e) => return Err::<(), YokaiErr>(
+                    YokaiErr::new(&e.to_string())
+                )
+            };
+            REPORT_RESOLVED
+        },
+        ACTION_KICK => {
+            let _rank: () = match ensure_outranks(
+                admin,
+                &report.reported_user,
+                &mut tx
+            ).await {
+                Ok(_f) => {},
+                Err(e) => return Err::<(), YokaiErr>(
+                    YokaiErr::new(&e.to_string())
+                )
+            };
+            purged = match purge_account(
+                &report.reported_user,
+                &mut tx
+            ).await {
+                Ok(stored) => stored,
+                Err(e) => return Err::<(), YokaiErr>(
+                    YokaiErr::new(&e.to_string())
+                )
+            };
+            let _del_op: () = match remove_account(
+                &report.reported_user,
+                &mut tx
+            ).await {
+                Ok(_f) => {},
+                Err(e) => return Err::<(), YokaiErr>(
+                    YokaiErr::new(&e.to_string())
+                )
+            };
+            REPORT_RESOLVED
+        },
+        _ => return Err::<(), YokaiErr>(
+            YokaiErr::new("Unknown moderation action.")
+        )
+    };
+    let _update_op: () = match query!(
+        "UPDATE reports SET status = $1, resolved = $2, resolved_by = $3 WHERE report_id = $4",
+        status,
+        rfc2282(),
+        admin,
+        report.report_id
+    )
+        .execute(&mut *tx)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let details: String = match note {
+        Some(note) => format!("{}: {}", action, note),
+        None => action.to_string()
+    };
+    let _audit: () = match record_audit(
+        admin,
+        AUDIT_REPORT_RESOLVE,
+        &Some(report.report_id.clone()),
+        &Some(details),
+        ip,
+        &mut tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _commit: () = match tx.commit().await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    remove_stored_files(&purged);
+    Ok(())
+}
+
+// used.
+pub async fn suspend_user(
+    actor: &str,
+    username: &str,
+    reason: &str,
+    until: &Option<String>,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), YokaiErr>{
+    if reason.trim().is_empty() || !check_message(reason){
+        return Err::<(), YokaiErr>(
+            YokaiErr::new(
+                &format!(
+                    "A suspension needs a reason of at most {} bytes.",
+                    MAX_MESSAGE_BYTES
+                )
+            )
+        );
+    }
+    if let Some(end) = until {
+        let since: i64 = match seconds_since(end){
+            Ok(since) => since,
+            Err(e) => return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        if since >= 0 {
+            return Err::<(), YokaiErr>(
+                YokaiErr::new("A suspension must end in the future.")
+            );
+        }
+    }
+    let _rank: () = match ensure_outranks(
+        actor,
+        username,
+        tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _guard: () = match ensure_admin_remains(
+        username,
+        tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _update_op: () = match query!(
+        "UPDATE users SET suspended_at = $1, suspended_until = $2, suspension_reason = $3 WHERE username = $4",
+        rfc2282(),
+        *until,
+        reason,
+        username
+    )
+        .execute(&mut **tx)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_tokens: () = match query!(
+        "DELETE FROM user_api_tokens WHERE owner = $1",
+        username
+    )
+        .execute(&mut **tx)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn unsuspend_user(
+    actor: &str,
+    username: &str,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), YokaiErr>{
+    let _rank: () = match ensure_outranks(
+        actor,
+        username,
+        tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _update_op: () = match query!(
+        "UPDATE users SET suspended_at = NULL, suspended_until = NULL, suspension_reason = NULL WHERE username = $1",
+        username
+    )
+        .execute(&mut **tx)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn ensure_outranks(
+    actor: &str,
+    target: &str,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), YokaiErr>{
+    let actor_obj: User = match query_as!(
+        User,
+        "SELECT * FROM users WHERE username = $1",
+        actor
+    )
+        .fetch_one(&mut **tx)
+        .await
+    {
+        Ok(actor_obj) => actor_obj,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let target_obj: User = match query_as!(
+        User,
+        "SELECT * FROM users WHERE username = $1",
+        target
+    )
+        .fetch_one(&mut **tx)
+        .await
+    {
+        Ok(target_obj) => target_obj,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if outranks(&actor_obj, &target_obj){
+        Ok(())
+    }
+    else {
+        Err::<(), YokaiErr>(
+            YokaiErr::new("Cannot act on a user with an equal or higher role.")
+        )
+    }
+}
+
+// used.
+pub async fn ensure_admin_remains(
+    username: &str,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), YokaiErr>{
+    let admins: Vec<User> = match query_as!(
+        User,
+        "SELECT * FROM users WHERE user_role = $1 ORDER BY username FOR NO KEY UPDATE",
+        ROLE_ADMIN
+    )
+        .fetch_all(&mut **tx)
+        .await 
+    {
+        Ok(admins) => admins,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let others: usize = admins
+        .iter()
+        .filter(|admin| admin.username != username && !is_suspended(admin))
+        .count();
+    let is_admin: bool = admins
+        .iter()
+        .any(|admin| admin.username == username);
+    if is_admin && others == 0 {
+        return Err::<(), YokaiErr>(
+            YokaiErr::new("Cannot remove the last administrator.")
+        );
+    }
+    Ok(())
+}
+
+// used.
+pub async fn set_user_role(
+    username: &str,
+    role: &str,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), YokaiErr>{
+    if !is_valid_role(role){
+        return Err::<(), YokaiErr>(
+            YokaiErr::new("Unknown role.")
+        );
+    }
+    if role != ROLE_ADMIN {
+        let _guard: () = match ensure_admin_remains(
+            username,
+            tx
+        ).await {
+            Ok(_f) => {},
+            Err(e) => return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+    }
+    let updated: u64 = match query!(
+        "UPDATE users SET user_role = $1 WHERE username = $2",
+        role,
+        username
+    )
+        .execute(&mut **tx)
+        .await
+    {
+        Ok(feedback) => feedback.rows_affected(),
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if updated == 0 {
+        return Err::<(), YokaiErr>(
+            YokaiErr::new("No such user exists.")
+        );
+    }
+    Ok(())
+}
+
+// used.
+pub async fn record_audit(
+    actor: &str,
+    action: &str,
+    target: &Option<String>,
+    details: &Option<String>,
+    ip: &Option<String>,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), YokaiErr>{
+    let _insert_op: () = match query!(
+        "INSERT INTO audit_log (actor, action, target, details, ip, created) VALUES ($1, $2, $3, $4, $5, $6)",
+        actor,
+        action,
+        *target,
+        *details,
+        *ip,
+        rfc2282(),
+    )
+        .execute(&mut **tx)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(())
+}
+
+// used.
+pub async fn get_audit_entries(
+    actor: &Option<String>,
+    action: &Option<String>,
+    limit: &Option<i64>,
+    offset: &i64,
+    pool: &Pool<Postgres>
+) -> Result<Vec<AuditEntry>, YokaiErr>{
+    let objects: Vec<AuditEntry> = match query_as!(
+        AuditEntry,
+        "SELECT * FROM audit_log WHERE ($1::text IS NULL OR actor = $1) AND ($2::text IS NULL OR action = $2) ORDER BY seq DESC LIMIT $3 OFFSET $4",
+        *actor,
+        *action,
+        *limit,
+        offset
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<AuditEntry>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn get_audit_page(
+    actor: &Option<String>,
+    action: &Option<String>,
+    before: &Option<i64>,
+    limit: &i64,
+    pool: &Pool<Postgres>
+) -> Result<Vec<AuditEntry>, YokaiErr>{
+    let objects: Vec<AuditEntry> = match query_as!(
+        AuditEntry,
+        "SELECT * FROM audit_log WHERE ($1::text IS NULL OR actor = $1) AND ($2::text IS NULL OR action = $2) AND ($3::bigint IS NULL OR seq < $3) ORDER BY seq DESC LIMIT $4",
+        *actor,
+        *action,
+        *before,
+        limit
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<AuditEntry>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn count_audit_entries(
+    actor: &Option<String>,
+    action: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<i64, YokaiErr>{
+    let count: Option<i64> = match query_scalar!(
+        "SELECT COUNT(*) FROM audit_log WHERE ($1::text IS NULL OR actor = $1) AND ($2::text IS NULL OR action = $2)",
+        *actor,
+        *action
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(count) => count,
+        Err(e) => return Err::<i64, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(count.unwrap_or(0))
+}
+
+// used.
+pub async fn search_users(
+    search: &Option<String>,
+    limit: &i64,
+    offset: &i64,
+    pool: &Pool<Postgres>
+) -> Result<Vec<User>, YokaiErr>{
+    let pattern: Option<String> = search
+        .as_ref()
+        .map(|term| like_pattern(term));
+    let objects: Vec<User> = match query_as!(
+        User,
+        "SELECT * FROM users WHERE $1::text IS NULL OR username ILIKE $1 ESCAPE '\\' OR display_name ILIKE $1 ESCAPE '\\' ORDER BY username LIMIT $2 OFFSET $3",
+        pattern,
+        limit,
+        offset
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<User>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn count_users(
+    search: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<i64, YokaiErr>{
+    let pattern: Option<String> = search
+        .as_ref()
+        .map(|term| like_pattern(term));
+    let count: Option<i64> = match query_scalar!(
+        "SELECT COUNT(*) FROM users WHERE $1::text IS NULL OR username ILIKE $1 ESCAPE '\\' OR display_name ILIKE $1 ESCAPE '\\'",
+        pattern
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(count) => count,
+        Err(e) => return Err::<i64, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(count.unwrap_or(0))
+}
+
+// used.
+pub async fn count_user_messages(
+    user: &str,
+    pool: &Pool<Postgres>
+) -> Result<i64, YokaiErr>{
+    let count: Option<i64> = match query_scalar!(
+        "SELECT COUNT(*) FROM messages WHERE sender = $1 AND is_system = false",
+        user
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(count) => count,
+        Err(e) => return Err::<i64, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(count.unwrap_or(0))
+}
+
+// used.
+pub async fn count_chats(
+    is_group: &bool,
+    pool: &Pool<Postgres>
+) -> Result<i64, YokaiErr>{
+    let count: Option<i64> = match query_scalar!(
+        "SELECT COUNT(*) FROM chats WHERE is_group = $1",
+        is_group
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(count) => count,
+        Err(e) => return Err::<i64, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(count.unwrap_or(0))
+}
+
+// used.
+pub async fn count_messages(
+    pool: &Pool<Postgres>
+) -> Result<i64, YokaiErr>{
+    let count: Option<i64> = match query_scalar!(
+        "SELECT COUNT(*) FROM messages WHERE is_system = false"
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(count) => count,
+        Err(e) => return Err::<i64, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(count.unwrap_or(0))
+}
+
+// used.
+pub async fn get_suspended_users(
+    pool: &Pool<Postgres>
+) -> Result<Vec<User>, YokaiErr>{
+    let objects: Vec<User> = match query_as!(
+        User,
+        "SELECT * FROM users WHERE suspended_at IS NOT NULL"
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<User>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let suspended: Vec<User> = objects
+        .into_iter()
+        .filter(is_suspended)
+        .collect::<Vec<User>>();
+    Ok(suspended)
+}
+
+// used.
+pub async fn create_invite_code(
+    inv_code: &str,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<InviteCode, YokaiErr>{
+    let id: String = hash_string(
+        &format!(
+            "{}{}",
+            inv_code,
+            rfc2282()
+        )
+    );
+    let code: InviteCode = InviteCode{
+        code_id: id, 
+        invite_code: inv_code.to_string() 
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO invite_codes (code_id, invite_code) VALUES ($1, $2)",
+        code.code_id,
+        code.invite_code,
+    )
+        .execute(&mut **tx)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<InviteCode, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let fetched: InviteCode = match query_as!(
+        InviteCode,
+        "SELECT * FROM invite_codes WHERE code_id = $1",
+        code.code_id
+    )
+        .fetch_one(&mut **tx)
+        .await
+    {
+        Ok(code) => code,
+        Err(e) => return Err::<InviteCode, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(fetched)
+}
+
+// used.
+pub async fn get_code_by_id(
+    code_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<InviteCode, YokaiErr>{
+    let object: InviteCode = match query_as!(
+        InviteCode,
+        "SELECT * FROM invite_codes WHERE code_id = $1",
+        code_id
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(object) => object,
+        Err(e) => return Err::<InviteCode, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(object)
+}
+
+// used.
+pub async fn delete_invite_code(
+    code_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let code: InviteCode = match get_code_by_id(
+        code_id,
+        pool
+    ).await {
+        Ok(code) => code,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let del_op: () = match query!(
+        "DELETE FROM invite_codes WHERE code_id = $1",
+        code.code_id
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(del_op)
+}
+
+// used.
+pub async fn get_file_by_id(
+    file_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<UserFile, YokaiErr>{
+    let object: UserFile = match query_as!(
+        UserFile,
+        "SELECT * FROM user_files WHERE file_id = $1",
+        file_id
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(object) => object,
+        Err(e) => return Err::<UserFile, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(object)
+}
+
+pub async fn delete_user_file(
+    file_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let file: UserFile = match get_file_by_id(
+        file_id,
+        pool
+    ).await {
+        Ok(file) => file,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if Path::new(&file.file_path).exists() {
+        let _file_del_op: () = match remove_file(&file.file_path){
+            Ok(_f) => {},
+            Err(e) => return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+    }
+    let _del_variants: () = match delete_file_variants(
+        &file.file_id,
+        pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let del_op: () = match query!(
+        "DELETE FROM user_files WHERE file_id = $1",
+        file.file_id
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(del_op)
+}
+
+// used.
+pub async fn create_user_file(
+    user: &str,
+    file_path: &str,
+    file_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<UserFile, YokaiErr>{
+    let user_obj: User = match get_user_by_id(
+        user,
+        pool
+    ).await {
+        Ok(user_obj) => user_obj,
+        Err(e) => return Err::<UserFile, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let file: UserFile = UserFile {
+        file_id: file_id.to_string(), 
+        file_path: file_path.to_string(), 
+        file_owner: user_obj.username,
+        missing: false,
+        scan_verdict: None,
+        file_size: file_size(file_path) as i64
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO user_files (file_id, file_path, file_owner, missing, file_size) VALUES ($1, $2, $3, $4, $5)",
+        file.file_id,
+        file.file_path,
+        file.file_owner,
+        file.missing,
+        file.file_size
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<UserFile, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let fetched: UserFile = match get_file_by_id(
+        &file.file_id,
+        pool
+    ).await {
+        Ok(fetched) => fetched,
+        Err(e) => return Err::<UserFile, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(fetched)
+}
+
+// used.
+pub async fn get_all_files(
+    pool: &Pool<Postgres>
+) -> Result<Vec<UserFile>, YokaiErr>{
+    let objects: Vec<UserFile> = match query_as!(
+        UserFile,
+        "SELECT * FROM user_files"
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<UserFile>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn set_file_missing(
+    file_id: &str,
+    missing: &bool,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let update_op: () = match query!(
+        "UPDATE user_files SET missing = $1 WHERE file_id = $2",
+        missing,
+        file_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(update_op)
+}
+
+// used.
+pub async fn set_file_scan(
+    file_id: &str,
+    scan_verdict: &str,
+    file_path: &str,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let update_op: () = match query!(
+        "UPDATE user_files SET scan_verdict = $1, file_path = $2 WHERE file_id = $3",
+        scan_verdict,
+        file_path,
+        file_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(update_op)
+}
+
+// used.
+pub async fn set_file_size(
+    file_id: &str,
+    file_size: &i64,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let update_op: () = match query!(
+        "UPDATE user_files SET file_size = $1 WHERE file_id = $2",
+        file_size,
+        file_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(update_op)
+}
+
+// used.
+pub async fn count_files(
+    owner: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<i64, YokaiErr>{
+    let count: Option<i64> = match query_scalar!(
+        "SELECT COUNT(*) FROM user_files WHERE $1::text IS NULL OR file_owner = $1",
+        owner.as_deref()
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(count) => count,
+        Err(e) => return Err::<i64, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(count.unwrap_or(0))
+}
+
+// used.
+pub async fn storage_used(
+    owner: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<i64, YokaiErr>{
+    let total: Option<i64> = match query_scalar!(
+        "SELECT COALESCE(SUM(file_size), 0)::bigint FROM user_files WHERE $1::text IS NULL OR file_owner = $1",
+        owner.as_deref()
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(total) => total,
+        Err(e) => return Err::<i64, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(total.unwrap_or(0))
+}
+
+// used.
+pub async fn get_all_file_variants(
+    pool: &Pool<Postgres>
+) -> Result<Vec<FileVariant>, YokaiErr>{
+    let objects: Vec<FileVariant> = match query_as!(
+        FileVariant,
+        "SELECT * FROM file_variants"
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<FileVariant>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn file_shared_with(
+    file_id: &str,
+    user: &str,
+    pool: &Pool<Postgres>
+) -> bool {
+    let objects: Vec<Message> = match query_as!(
+        Message,
+        "SELECT * FROM messages WHERE attachment = $1 AND (sender = $2 OR receiver = $2 OR chat_id IN (SELECT chat_id FROM chat_members WHERE username = $2))",
+        file_id,
+        user
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(_e) => return false
+    };
+    !objects.is_empty()
+}
+
+// used.
+pub async fn create_file_variant(
+    file_id: &str,
+    variant: &str,
+    file_path: &str,
+    pool: &Pool<Postgres>
+) -> Result<FileVariant, YokaiErr>{
+    let file: UserFile = match get_file_by_id(
+        file_id,
+        pool
+    ).await {
+        Ok(file) => file,
+        Err(e) => return Err::<FileVariant, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let file_variant: FileVariant = FileVariant {
+        variant_id: hash_string(
+            &format!(
+                "{}{}",
+                &file.file_id,
+                variant
+            )
+        ),
+        file_id: file.file_id,
+        variant: variant.to_string(),
+        file_path: file_path.to_string()
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO file_variants (variant_id, file_id, variant, file_path) VALUES ($1, $2, $3, $4)",
+        file_variant.variant_id,
+        file_variant.file_id,
+        file_variant.variant,
+        file_variant.file_path
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<FileVariant, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let fetched: FileVariant = match get_file_variant(
+        &file_variant.file_id,
+        &file_variant.variant,
+        pool
+    ).await {
+        Ok(fetched) => fetched,
+        Err(e) => return Err::<FileVariant, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(fetched)
+}
+
+// used.
+pub async fn get_file_variant(
+    file_id: &str,
+    variant: &str,
+    pool: &Pool<Postgres>
+) -> Result<FileVariant, YokaiErr>{
+    let object: FileVariant = match query_as!(
+        FileVariant,
+        "SELECT * FROM file_variants WHERE file_id = $1 AND variant = $2",
+        file_id,
+        variant
+    )
+        .fetch_one(pool)
+        .await 
+    {
+        Ok(object) => object,
+        Err(e) => return Err::<FileVariant, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(object)
+}
+
+// used.
+pub async fn get_file_variants(
+    file_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<Vec<FileVariant>, YokaiErr>{
+    let objects: Vec<FileVariant> = match query_as!(
+        FileVariant,
+        "SELECT * FROM file_variants WHERE file_id = $1",
+        file_id
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<FileVariant>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn delete_file_variants(
+    file_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let variants: Vec<FileVariant> = match get_file_variants(
+        file_id,
+        pool
+    ).await {
+        Ok(variants) => variants,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_op: () = match query!(
+        "DELETE FROM file_variants WHERE file_id = $1",
+        file_id
+    )
+        .execute(pool)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    for variant in variants {
+        if Path::new(&variant.file_path).exists() {
+            let _file_del_op: () = match remove_file(&variant.file_path){
+                Ok(_f) => {},
+                Err(e) => return Err::<(), YokaiErr>(
+                    YokaiErr::new(&e.to_string())
+                )
+            };
+        }
+    }
+    Ok(())
+}
+
+// used.
+pub async fn pfp_in_use(
+    file_id: &str,
+    pool: &Pool<Postgres>
+) -> bool {
+    let objects: Vec<User> = match query_as!(
+        User,
+        "SELECT * FROM users WHERE user_pfp_id = $1",
+        file_id
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(_e) => return false
+    };
+    !objects.is_empty()
+}
+
+// used.
+pub async fn attachment_in_use(
+    file_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<bool, YokaiErr>{
+    let in_use: Option<bool> = match query_scalar!(
+        "SELECT EXISTS (SELECT 1 FROM messages WHERE attachment = $1) OR EXISTS (SELECT 1 FROM scheduled_messages WHERE attachment = $1) OR EXISTS (SELECT 1 FROM users WHERE user_pfp_id = $1)",
+        file_id
     )
-        .fetch_all(pool)
-        .await 
+        .fetch_one(pool)
+        .await
     {
-        Ok(objects) => objects,
-        Err(e) => return Err::<Chat, YokaiErr>(
+        Ok(in_use) => in_use,
+        Err(e) => return Err::<bool, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    if objects.len() == 1{
-        Ok(objects[0].clone())
-    }
-    else {
-        Err::<Chat, YokaiErr>(
-            YokaiErr::new("A chat could not be found.")
-        )
-    }
+    Ok(in_use.unwrap_or(true))
 }
 
 // used.
-pub async fn create_invite_code(
-    inv_code: &str,
+#[allow(clippy::too_many_arguments)]
+pub async fn create_upload_session(
+    session_id: &str,
+    owner: &str,
+    file_name: &str,
+    file_path: &str,
+    total_size: &i64,
+    checksum: &str,
+    max_size: &i64,
     pool: &Pool<Postgres>
-) -> Result<InviteCode, YokaiErr>{
-    let id: String = hash_string(
-        &format!(
-            "{}{}",
-            inv_code,
-            rfc2282()
+) -> Result<UploadSession, YokaiErr>{
+    if *total_size <= 0 || *total_size > *max_size {
+        return Err::<UploadSession, YokaiErr>(
+            YokaiErr::new("Upload size is not within the allowed limits.")
+        );
+    }
+    let owner_obj: User = match get_user_by_id(
+        owner,
+        pool
+    ).await {
+        Ok(owner_obj) => owner_obj,
+        Err(e) => return Err::<UploadSession, YokaiErr>(
+            YokaiErr::new(&e.to_string())
         )
-    );
-    let code: InviteCode = InviteCode{
-        code_id: id, 
-        invite_code: inv_code.to_string() 
     };
-    let _insert_op = match query!(
-        "INSERT INTO invite_codes (code_id, invite_code) VALUES ($1, $2)",
-        code.code_id,
-        code.invite_code,
+    let open_sessions: i64 = match query_scalar!(
+        "SELECT COUNT(*) FROM upload_sessions WHERE owner = $1",
+        owner_obj.username
+    )
+        .fetch_one(pool)
+        .await
+    {
+        Ok(open_sessions) => open_sessions.unwrap_or(0),
+        Err(e) => return Err::<UploadSession, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if open_sessions >= MAX_OPEN_UPLOADS {
+        return Err::<UploadSession, YokaiErr>(
+            YokaiErr::new("Too many uploads are already in progress.")
+        );
+    }
+    let session: UploadSession = UploadSession {
+        session_id: session_id.to_string(),
+        owner: owner_obj.username,
+        file_name: file_name.to_string(),
+        file_path: file_path.to_string(),
+        total_size: *total_size,
+        received: 0,
+        checksum: checksum.to_uppercase(),
+        created: rfc2282(),
+        updated: rfc2282()
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO upload_sessions (session_id, owner, file_name, file_path, total_size, received, checksum, created, updated) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        session.session_id,
+        session.owner,
+        session.file_name,
+        session.file_path,
+        session.total_size,
+        session.received,
+        session.checksum,
+        session.created,
+        session.updated
     )
         .execute(pool)
         .await
     {
         Ok(_feedback) => {},
-        Err(e) => return Err::<InviteCode, YokaiErr>(
+        Err(e) => return Err::<UploadSession, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    let fetched: InviteCode = match get_code_by_id(
-        &code.code_id,
+    let fetched: UploadSession = match get_upload_session(
+        &session.session_id,
         pool
     ).await {
-        Ok(code) => code,
-        Err(e) => return Err::<InviteCode, YokaiErr>(
+        Ok(fetched) => fetched,
+        Err(e) => return Err::<UploadSession, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
@@ -786,20 +4903,20 @@ pub async fn create_invite_code(
 }
 
 // used.
-pub async fn get_code_by_id(
-    code_id: &str,
+pub async fn get_upload_session(
+    session_id: &str,
     pool: &Pool<Postgres>
-) -> Result<InviteCode, YokaiErr>{
-    let object: InviteCode = match query_as!(
-        InviteCode,
-        "SELECT * FROM invite_codes WHERE code_id = $1",
-        code_id
+) -> Result<UploadSession, YokaiErr>{
+    let object: UploadSession = match query_as!(
+        UploadSession,
+        "SELECT * FROM upload_sessions WHERE session_id = $1",
+        session_id
     )
         .fetch_one(pool)
         .await 
     {
         Ok(object) => object,
-        Err(e) => return Err::<InviteCode, YokaiErr>(
+        Err(e) => return Err::<UploadSession, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
@@ -807,134 +4924,158 @@ pub async fn get_code_by_id(
 }
 
 // used.
-pub async fn delete_invite_code(
-    code_id: &str,
-    pool: &Pool<Postgres>
-) -> Result<(), YokaiErr>{
-    let code: InviteCode = match get_code_by_id(
-        code_id,
-        pool
-    ).await {
-        Ok(code) => code,
-        Err(e) => return Err::<(), YokaiErr>(
-            YokaiErr::new(&e.to_string())
-        )
-    };
-    let del_op: () = match query!(
-        "DELETE FROM invite_codes WHERE code_id = $1",
-        code.code_id
+pub async fn lock_upload_session(
+    session_id: &str,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<UploadSession, YokaiErr>{
+    let object: UploadSession = match query_as!(
+        UploadSession,
+        "SELECT * FROM upload_sessions WHERE session_id = $1 FOR UPDATE",
+        session_id
     )
-        .execute(pool)
+        .fetch_one(&mut **tx)
         .await 
     {
-        Ok(_f) => {},
-        Err(e) => return Err::<(), YokaiErr>(
+        Ok(object) => object,
+        Err(e) => return Err::<UploadSession, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    Ok(del_op)
+    Ok(object)
 }
 
 // used.
-pub async fn get_file_by_id(
+pub async fn complete_upload_session(
+    session: &UploadSession,
+    file_path: &str,
     file_id: &str,
-    pool: &Pool<Postgres>
+    tx: &mut Transaction<'_, Postgres>
 ) -> Result<UserFile, YokaiErr>{
-    let object: UserFile = match query_as!(
-        UserFile,
-        "SELECT * FROM user_files WHERE file_id = $1",
-        file_id
+    let file: UserFile = UserFile {
+        file_id: file_id.to_string(),
+        file_path: file_path.to_string(),
+        file_owner: session.owner.clone(),
+        missing: false,
+        scan_verdict: None,
+        file_size: session.total_size
+    };
+    let _insert_op: () = match query!(
+        "INSERT INTO user_files (file_id, file_path, file_owner, missing, file_size) VALUES ($1, $2, $3, $4, $5)",
+        file.file_id,
+        file.file_path,
+        file.file_owner,
+        file.missing,
+        file.file_size
     )
-        .fetch_one(pool)
+        .execute(&mut **tx)
+        .await
+    {
+        Ok(_feedback) => {},
+        Err(e) => return Err::<UserFile, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_op: () = match query!(
+        "DELETE FROM upload_sessions WHERE session_id = $1",
+        session.session_id
+    )
+        .execute(&mut **tx)
         .await 
     {
-        Ok(object) => object,
+        Ok(_f) => {},
         Err(e) => return Err::<UserFile, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    Ok(object)
+    Ok(file)
 }
 
-pub async fn delete_user_file(
-    file_id: &str,
+// used.
+pub async fn get_upload_sessions(
     pool: &Pool<Postgres>
-) -> Result<(), YokaiErr>{
-    let file: UserFile = match get_file_by_id(
-        file_id,
-        pool
-    ).await {
-        Ok(file) => file,
-        Err(e) => return Err::<(), YokaiErr>(
-            YokaiErr::new(&e.to_string())
-        )
-    };
-    let _del_op: () = match query!(
-        "DELETE FROM user_files WHERE file_id = $1",
-        file.file_id
+) -> Result<Vec<UploadSession>, YokaiErr>{
+    let objects: Vec<UploadSession> = match query_as!(
+        UploadSession,
+        "SELECT * FROM upload_sessions"
     )
-        .execute(pool)
+        .fetch_all(pool)
         .await 
     {
-        Ok(_f) => {},
-        Err(e) => return Err::<(), YokaiErr>(
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<UploadSession>, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    let file_del_op: () = match remove_file(file.file_path){
-        Ok(_f) => {},
+    Ok(objects)
+}
+
+// used.
+pub async fn advance_upload_session(
+    session_id: &str,
+    old_offset: &i64,
+    new_offset: &i64,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), YokaiErr>{
+    let affected: u64 = match query!(
+        "UPDATE upload_sessions SET received = $1, updated = $2 WHERE session_id = $3 AND received = $4",
+        new_offset,
+        rfc2282(),
+        session_id,
+        old_offset
+    )
+        .execute(&mut **tx)
+        .await
+    {
+        Ok(feedback) => feedback.rows_affected(),
         Err(e) => return Err::<(), YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    Ok(file_del_op)
+    if affected == 1 {
+        Ok(())
+    }
+    else {
+        Err::<(), YokaiErr>(
+            YokaiErr::new("Upload offset does not match.")
+        )
+    }
 }
 
 // used.
-pub async fn create_user_file(
-    user: &str,
-    file_path: &str,
-    file_id: &str,
+pub async fn delete_upload_session(
+    session_id: &str,
     pool: &Pool<Postgres>
-) -> Result<UserFile, YokaiErr>{
-    let user_obj: User = match get_user_by_id(
-        user,
+) -> Result<(), YokaiErr>{
+    let session: UploadSession = match get_upload_session(
+        session_id,
         pool
     ).await {
-        Ok(user_obj) => user_obj,
-        Err(e) => return Err::<UserFile, YokaiErr>(
+        Ok(session) => session,
+        Err(e) => return Err::<(), YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    let file: UserFile = UserFile {
-        file_id: file_id.to_string(), 
-        file_path: file_path.to_string(), 
-        file_owner: user_obj.username
-    };
-    let _insert_op = match query!(
-        "INSERT INTO user_files (file_id, file_path, file_owner) VALUES ($1, $2, $3)",
-        file.file_id,
-        file.file_path,
-        file.file_owner
+    let _del_op: () = match query!(
+        "DELETE FROM upload_sessions WHERE session_id = $1",
+        session.session_id
     )
         .execute(pool)
-        .await
+        .await 
     {
-        Ok(_feedback) => {},
-        Err(e) => return Err::<UserFile, YokaiErr>(
-            YokaiErr::new(&e.to_string())
-        )
-    };
-    let fetched: UserFile = match get_file_by_id(
-        &file.file_id,
-        pool
-    ).await {
-        Ok(fetched) => fetched,
-        Err(e) => return Err::<UserFile, YokaiErr>(
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    Ok(fetched)
+    if Path::new(&session.file_path).exists() {
+        let _file_del_op: () = match remove_file(&session.file_path){
+            Ok(_f) => {},
+            Err(e) => return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+    }
+    Ok(())
 }
 
 // used.
@@ -978,251 +5119,358 @@ pub async fn create_host_info(
             hostname: hostname.to_string(), 
             primary_color: primary_color.to_string(), 
             secondary_color: secondary_color.to_string(), 
-            tertiary_color: tertiary_color.to_string()
+            tertiary_color: tertiary_color.to_string(),
+            instance_name: None,
+            description: None,
+            logo_file_id: None,
+            favicon_file_id: None,
+            terms: None,
+            privacy_policy: None,
+            contact_address: None
         };
-        let _insert_op = match query!(
+        let _insert_op: () = match query!(
             "INSERT INTO host_info (hostname, primary_color, secondary_color, tertiary_color) VALUES ($1, $2, $3, $4)",
             host_info.hostname,
             host_info.primary_color,
-            host_info.secondary_color,
-            host_info.tertiary_color
-        )
-            .execute(pool)
-            .await
-        {
-            Ok(_feedback) => {},
-            Err(e) => return Err::<HostInformation, YokaiErr>(
-                YokaiErr::new(&e.to_string())
-            )
-        };
-        let info: HostInformation = match get_host_info(pool).await{
-            Ok(info) => info,
-            Err(e) => return Err::<HostInformation, YokaiErr>(
-                YokaiErr::new(&e.to_string())
-            )
-        };
-        if info.hostname ==host_info.hostname {
-            Ok(info)
-        }
-        else {
-            Err::<HostInformation, YokaiErr>(
-                YokaiErr::new("Error saving host information.")
-            )
-        }
-    }
-    else {
-        Err::<HostInformation, YokaiErr>(
-            YokaiErr::new("One or more invalid color strings received.")
-        )
-    }
-}
-
-// used.
-pub async fn edit_host_primary(
-    new_primary: &str,
-    pool: &Pool<Postgres>
-) -> Result<(), YokaiErr>{
-    if check_color_str(new_primary){
-        let info: HostInformation = match get_host_info(pool).await{
-            Ok(info) => info,
-            Err(e) => return Err::<(), YokaiErr>(
-                YokaiErr::new(&e.to_string())
-            )
-        };
-        let update_op: () = match query!(
-            "UPDATE host_info SET primary_color = $1 WHERE hostname = $2",
-            new_primary,
-            info.hostname
-        )
-            .execute(pool)
-            .await
-        {
-            Ok(_f) => {},
-            Err(e) => return Err::<(), YokaiErr>(
-                YokaiErr::new(&e.to_string())
-            )
-        };
-        Ok(update_op)
-    }
-    else {
-        Err::<(), YokaiErr>(
-            YokaiErr::new("Invalid color received.")
-        )
-    }
-}
-
-// used.
-pub async fn edit_host_secondary(
-    new_secondary: &str,
-    pool: &Pool<Postgres>
-) -> Result<(), YokaiErr>{
-    if check_color_str(new_secondary){
-        let info: HostInformation = match get_host_info(pool).await{
-            Ok(info) => info,
-            Err(e) => return Err::<(), YokaiErr>(
-                YokaiErr::new(&e.to_string())
-            )
-        };
-        let update_op: () = match query!(
-            "UPDATE host_info SET secondary_color = $1 WHERE hostname = $2",
-            new_secondary,
-            info.hostname
+            host_info.secondary_color,
+            host_info.tertiary_color
         )
             .execute(pool)
             .await
         {
-            Ok(_f) => {},
-            Err(e) => return Err::<(), YokaiErr>(
+            Ok(_feedback) => {},
+            Err(e) => return Err::<HostInformation, YokaiErr>(
                 YokaiErr::new(&e.to_string())
             )
         };
-        Ok(update_op)
-    }
-    else {
-        Err::<(), YokaiErr>(
-            YokaiErr::new("Invalid color received.")
-        )
-    }
-}
-
-// used.
-pub async fn edit_host_tertiary(
-    new_tertiary: &str,
-    pool: &Pool<Postgres>
-) -> Result<(), YokaiErr>{
-    if check_color_str(new_tertiary){
         let info: HostInformation = match get_host_info(pool).await{
             Ok(info) => info,
-            Err(e) => return Err::<(), YokaiErr>(
+            Err(e) => return Err::<HostInformation, YokaiErr>(
                 YokaiErr::new(&e.to_string())
             )
         };
-        let update_op: () = match query!(
-            "UPDATE host_info SET tertiary_color = $1 WHERE hostname = $2",
-            new_tertiary,
-            info.hostname
-        )
-            .execute(pool)
-            .await
-        {
-            Ok(_f) => {},
-            Err(e) => return Err::<(), YokaiErr>(
-                YokaiErr::new(&e.to_string())
+        if info.hostname ==host_info.hostname {
+            Ok(info)
+        }
+        else {
+            Err::<HostInformation, YokaiErr>(
+                YokaiErr::new("Error saving host information.")
             )
-        };
-        Ok(update_op)
+        }
     }
     else {
-        Err::<(), YokaiErr>(
-            YokaiErr::new("Invalid color received.")
+        Err::<HostInformation, YokaiErr>(
+            YokaiErr::new("One or more invalid color strings received.")
         )
     }
 }
 
+// used.
+pub async fn edit_host_branding(
+    branding: &HostInformation,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), YokaiErr>{
+    if !check_color_str(&branding.primary_color) ||
+       !check_color_str(&branding.secondary_color) ||
+       !check_color_str(&branding.tertiary_color)
+    {
+        return Err::<(), YokaiErr>(
+            YokaiErr::new("One or more invalid color strings received.")
+        );
+    }
+    let update_op: () = match query!(
+        "UPDATE host_info SET primary_color = $1, secondary_color = $2, tertiary_color = $3, instance_name = $4, description = $5, logo_file_id = $6, favicon_file_id = $7, terms = $8, privacy_policy = $9, contact_address = $10 WHERE hostname = $11",
+        branding.primary_color,
+        branding.secondary_color,
+        branding.tertiary_color,
+        branding.instance_name,
+        branding.description,
+        branding.logo_file_id,
+        branding.favicon_file_id,
+        branding.terms,
+        branding.privacy_policy,
+        branding.contact_address,
+        branding.hostname
+    )
+        .execute(&mut **tx)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(update_op)
+}
+
 // used.
 pub async fn delete_account(
     user: &str,
     pool: &Pool<Postgres>
 ) -> Result<(), YokaiErr>{
-    let user_obj: User = match get_user_by_id(
+    let mut tx: Transaction<'_, Postgres> = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let stored: Vec<String> = match purge_account(
         user,
-        pool
+        &mut tx
     ).await {
-        Ok(user_obj) => user_obj,
+        Ok(stored) => stored,
         Err(e) => return Err::<(), YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    let chats: Vec<Chat> = match get_user_chats(
-        &user_obj.username,
-        pool
+    let _remove: () = match remove_account(
+        user,
+        &mut tx
     ).await {
-        Ok(chats) => chats,
+        Ok(_f) => {},
         Err(e) => return Err::<(), YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    for chat in chats {
-        let messages: Vec<Message> = match get_chat_messages(
-            &chat.chat_id,
-            pool
-        ).await {
-            Ok(messages) => messages,
-            Err(e) => return Err::<(), YokaiErr>(
-                YokaiErr::new(&e.to_string())
-            )
-        };
-        for message in messages {
-            let _del_msg: () = match delete_message(
-                &message.msg_id,
-                pool
-            ).await {
-                Ok(_f) => {},
-                Err(e) => return Err::<(), YokaiErr>(
-                    YokaiErr::new(&e.to_string())
-                )
-            };
-        }
-        let _del_chat: () = match delete_chat(
-            &chat.chat_id,
-            pool
-        ).await {
-            Ok(_f) => {},
-            Err(e) => return Err::<(), YokaiErr>(
-                YokaiErr::new(&e.to_string())
-            )
-        };
-    }
-    let user_files: Vec<UserFile> = match get_user_files(
+    let acc_del: () = match tx.commit().await {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    remove_stored_files(&stored);
+    Ok(acc_del)
+}
+
+// used.
+pub async fn purge_account(
+    user: &str,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<Vec<String>, YokaiErr>{
+    let user_obj: User = match query_as!(
+        User,
+        "SELECT * FROM users WHERE username = $1",
+        user
+    )
+        .fetch_one(&mut **tx)
+        .await 
+    {
+        Ok(user_obj) => user_obj,
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _guard: () = match ensure_admin_remains(
         &user_obj.username,
-        pool
+        tx
     ).await {
-        Ok(user_files) => user_files,
-        Err(e) => return Err::<(), YokaiErr>(
+        Ok(_f) => {},
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    for file in user_files {
-        let _del_file: () = match delete_user_file(
-            &file.file_id,
-            pool
+    let groups: Vec<String> = match query_scalar!(
+        "SELECT chat_id FROM chat_members WHERE username = $1 AND pending = false",
+        user_obj.username
+    )
+        .fetch_all(&mut **tx)
+        .await 
+    {
+        Ok(groups) => groups,
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    for group in groups {
+        let _leave_op: () = match leave_chat(
+            &group,
+            &user_obj.username,
+            tx
         ).await {
             Ok(_f) => {},
-            Err(e) => return Err::<(), YokaiErr>(
-                YokaiErr::new(&e.to_string())
-            )
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
         };
     }
-    let user_tokens: Vec<UserAPIToken> = match get_user_tokens(
-        &user_obj.username,
-        pool
-    ).await {
-        Ok(user_tokens) => user_tokens,
-        Err(e) => return Err::<(), YokaiErr>(
+    let direct: Vec<String> = match query_scalar!(
+        "SELECT chat_id FROM chats WHERE is_group = false AND (sender = $1 OR receiver = $1)",
+        user_obj.username
+    )
+        .fetch_all(&mut **tx)
+        .await 
+    {
+        Ok(direct) => direct,
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    for token in user_tokens {
-        let _del_token: () = match delete_token(
-            &token.token_id,
-            pool
+    for chat in direct {
+        let _del_chat: () = match clear_chat(
+            &chat,
+            tx
         ).await {
             Ok(_f) => {},
-            Err(e) => return Err::<(), YokaiErr>(
-                YokaiErr::new(&e.to_string())
-            )
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
         };
     }
-    let acc_del: () = match delete_user(
-        &user_obj.username,
-        pool
+    let _del_stars: () = match query!(
+        "DELETE FROM starred_messages WHERE username = $1",
+        user_obj.username
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_scheduled: () = match query!(
+        "DELETE FROM scheduled_messages WHERE sender = $1",
+        user_obj.username
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_blocks: () = match query!(
+        "DELETE FROM user_blocks WHERE blocker = $1 OR blocked = $1",
+        user_obj.username
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_invites: () = match query!(
+        "DELETE FROM chat_members WHERE username = $1 AND pending = true",
+        user_obj.username
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _clear_inviter: () = match query!(
+        "UPDATE chat_members SET invited_by = NULL WHERE invited_by = $1",
+        user_obj.username
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let stored: Vec<String> = match query_scalar!(
+        "SELECT file_path FROM file_variants WHERE file_id IN (SELECT file_id FROM user_files WHERE file_owner = $1) UNION ALL SELECT file_path FROM user_files WHERE file_owner = $1",
+        user_obj.username
+    )
+        .fetch_all(&mut **tx)
+        .await 
+    {
+        Ok(stored) => stored.into_iter().flatten().collect(),
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_variants: () = match query!(
+        "DELETE FROM file_variants WHERE file_id IN (SELECT file_id FROM user_files WHERE file_owner = $1)",
+        user_obj.username
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_files: () = match query!(
+        "DELETE FROM user_files WHERE file_owner = $1",
+        user_obj.username
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_tokens: () = match query!(
+        "DELETE FROM user_api_tokens WHERE owner = $1",
+        user_obj.username
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(stored)
+}
+
+// used.
+pub fn remove_stored_files(
+    paths: &[String]
+) {
+    for path in paths {
+        if Path::new(path).exists() {
+            // Anything left behind is picked up by the orphan collector.
+            let _ = remove_file(path);
+        }
+    }
+}
+
+// used.
+pub async fn remove_account(
+    user: &str,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<(), YokaiErr>{
+    let _guard: () = match ensure_admin_remains(
+        user,
+        tx
     ).await {
         Ok(_f) => {},
         Err(e) => return Err::<(), YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    Ok(acc_del)
+    let deleted: u64 = match query!(
+        "DELETE FROM users WHERE username = $1",
+        user
+    )
+        .execute(&mut **tx)
+        .await 
+    {
+        Ok(feedback) => feedback.rows_affected(),
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if deleted == 0 {
+        return Err::<(), YokaiErr>(
+            YokaiErr::new("No such user exists.")
+        );
+    }
+    Ok(())
 }
 
 // used.
@@ -1249,7 +5497,25 @@ pub async fn create_api_token(
             YokaiErr::new(&e.to_string())
         )
     };
+    if verified && is_suspended(&user_obj){
+        return Err::<UserAPIToken, YokaiErr>(
+            YokaiErr::new("This account is suspended.")
+        );
+    }
     if verified {
+        let _login_op: () = match query!(
+            "UPDATE users SET last_login = $1 WHERE username = $2",
+            rfc2282(),
+            user_obj.username
+        )
+            .execute(pool)
+            .await
+        {
+            Ok(_f) => {},
+            Err(e) => return Err::<UserAPIToken, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
         let token_id: String = hash_string(
             &format!(
                 "{}{}{}",
@@ -1264,7 +5530,7 @@ pub async fn create_api_token(
             token:token_str, 
             owner: user_obj.username
         };
-        let _insert_op = match query!(
+        let _insert_op: () = match query!(
             "INSERT INTO user_api_tokens (token_id, token, owner) VALUES ($1, $2, $3)",
             token.token_id,
             token.token,
@@ -1344,7 +5610,7 @@ pub async fn get_user_by_token(
     pool: &Pool<Postgres>
 ) -> Result<User, YokaiErr>{
     let fetched: UserAPIToken = match get_token_by_token(
-        &token,
+        token,
         pool
     ).await {
         Ok(fetched) => fetched,
@@ -1361,6 +5627,11 @@ pub async fn get_user_by_token(
             YokaiErr::new(&e.to_string())
         )
     };
+    if is_suspended(&user_obj){
+        return Err::<User, YokaiErr>(
+            YokaiErr::new("This account is suspended.")
+        );
+    }
     Ok(user_obj)
 }
 
@@ -1398,7 +5669,7 @@ pub async fn get_user_chats(
     pool: &Pool<Postgres>
 ) -> Result<Vec<Chat>, YokaiErr>{
     let user_obj: User = match get_user_by_id(
-        &user,
+        user,
         pool
     ).await {
         Ok(user_obj) => user_obj,
@@ -1406,33 +5677,47 @@ pub async fn get_user_chats(
             YokaiErr::new(&e.to_string())
         )
     };
-    let mut chats_s: Vec<Chat> = match query_as!(
+    let mut chats_s: Vec<Chat> = match query_as!(
+        Chat,
+        "SELECT * FROM chats WHERE sender = $1 AND is_group = false",
+        &user_obj.username
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(chats_s) => chats_s,
+        Err(e) => return Err::<Vec<Chat>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut chats_r: Vec<Chat> = match query_as!(
         Chat,
-        "SELECT * FROM chats WHERE sender = $1",
+        "SELECT * FROM chats WHERE receiver = $1",
         &user_obj.username
     )
         .fetch_all(pool)
         .await 
     {
-        Ok(chats_s) => chats_s,
+        Ok(chats_r) => chats_r,
         Err(e) => return Err::<Vec<Chat>, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
-    let mut chats_r: Vec<Chat> = match query_as!(
+    let mut chats_g: Vec<Chat> = match query_as!(
         Chat,
-        "SELECT * FROM chats WHERE receiver = $1",
+        "SELECT * FROM chats WHERE chat_id IN (SELECT chat_id FROM chat_members WHERE username = $1 AND pending = false)",
         &user_obj.username
     )
         .fetch_all(pool)
         .await 
     {
-        Ok(chats_r) => chats_r,
+        Ok(chats_g) => chats_g,
         Err(e) => return Err::<Vec<Chat>, YokaiErr>(
             YokaiErr::new(&e.to_string())
         )
     };
     chats_s.append(&mut chats_r);
+    chats_s.append(&mut chats_g);
     Ok(chats_s)
 }
 
@@ -1451,32 +5736,48 @@ pub async fn get_user_contacts(
             YokaiErr::new(&e.to_string())
         )
     };
+    let mut usernames: Vec<String> = Vec::new();
     for chat in user_chats{
-        if chat.sender == user{
-            let contact: User = match get_user_by_id(
-                &chat.receiver,
-                pool
-            ).await {
-                Ok(user_obj) => user_obj,
-                Err(e) => return Err::<Vec<User>, YokaiErr>(
-                    YokaiErr::new(&e.to_string())
-                )
-            };
-            user_contacts.push(contact);
+        if awaiting_approval(&chat, user){
+            continue;
         }
-        else {
-            let contact: User = match get_user_by_id(
-                &chat.sender,
-                pool
-            ).await {
-                Ok(user_obj) => user_obj,
-                Err(e) => return Err::<Vec<User>, YokaiErr>(
-                    YokaiErr::new(&e.to_string())
-                )
-            };
-            user_contacts.push(contact);
+        let participants: Vec<String> = match chat_participants(
+            &chat,
+            pool
+        ).await {
+            Ok(participants) => participants,
+            Err(e) => return Err::<Vec<User>, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        for participant in participants {
+            if participant != user && !usernames.contains(&participant){
+                usernames.push(participant);
+            }
         }
     }
+    let blocks: Vec<UserBlock> = match get_user_blocks(
+        user,
+        pool
+    ).await {
+        Ok(blocks) => blocks,
+        Err(e) => return Err::<Vec<User>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    usernames.retain(|username| !blocks.iter().any(|block| block.blocked == *username));
+    for username in usernames {
+        let contact: User = match get_user_by_id(
+            &username,
+            pool
+        ).await {
+            Ok(user_obj) => user_obj,
+            Err(e) => return Err::<Vec<User>, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        user_contacts.push(contact);
+    }
     Ok(user_contacts)
 }
 
@@ -1486,7 +5787,7 @@ pub async fn get_user_files(
     pool: &Pool<Postgres>
 ) -> Result<Vec<UserFile>, YokaiErr>{
     let obj: User = match get_user_by_id(
-        &user,
+        user,
         pool
     ).await {
         Ok(user_obj) => user_obj,
@@ -1525,7 +5826,7 @@ pub async fn get_chat_messages(
     };
     let msgs: Vec<Message> = match query_as!(
         Message,
-        "SELECT * FROM messages WHERE chat_id = $1",
+        "SELECT * FROM messages WHERE chat_id = $1 AND (expires_at IS NULL OR expires_at::timestamptz > now()) ORDER BY published::timestamptz, msg_id",
         chat.chat_id
     )
         .fetch_all(pool)
@@ -1539,13 +5840,409 @@ pub async fn get_chat_messages(
     Ok(msgs)
 }
 
+// used.
+pub async fn get_thread_messages(
+    root_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<Vec<Message>, YokaiErr>{
+    let msgs: Vec<Message> = match query_as!(
+        Message,
+        "SELECT * FROM messages WHERE (msg_id = $1 OR thread_root = $1) AND (expires_at IS NULL OR expires_at::timestamptz > now()) ORDER BY published::timestamptz, msg_id",
+        root_id
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(msgs) => msgs,
+        Err(e) => return Err::<Vec<Message>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(msgs)
+}
+
+// used.
+pub async fn get_reply_counts(
+    chat_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<HashMap<String, i64>, YokaiErr>{
+    let rows = match query!(
+        "SELECT thread_root, COUNT(*) AS replies FROM messages WHERE chat_id = $1 AND thread_root IS NOT NULL AND (expires_at IS NULL OR expires_at::timestamptz > now()) GROUP BY thread_root",
+        chat_id
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(rows) => rows,
+        Err(e) => return Err::<HashMap<String, i64>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut counts: HashMap<String, i64> = HashMap::new();
+    for row in rows {
+        if let Some(thread_root) = row.thread_root {
+            counts.insert(thread_root, row.replies.unwrap_or(0));
+        }
+    }
+    Ok(counts)
+}
+
+// used.
+pub async fn mark_delivered(
+    chat_id: &str,
+    user: &str,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let update_op: () = match query!(
+        "UPDATE message_keys SET delivered_at = $1 WHERE recipient = $2 AND delivered_at IS NULL AND msg_id IN (SELECT msg_id FROM messages WHERE chat_id = $3 AND sender <> $2)",
+        rfc2282(),
+        user,
+        chat_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(update_op)
+}
+
+// used.
+pub async fn mark_read(
+    chat_id: &str,
+    up_to: &str,
+    user: &User,
+    pool: &Pool<Postgres>
+) -> Result<(), YokaiErr>{
+    let target: Message = match get_message_by_id(
+        up_to,
+        pool
+    ).await {
+        Ok(target) => target,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if target.chat_id != chat_id {
+        return Err::<(), YokaiErr>(
+            YokaiErr::new("Message does not belong to this chat.")
+        );
+    }
+    let now: String = rfc2282();
+    let read_at: Option<String> = if user.read_receipts {
+        Some(now.clone())
+    }
+    else {
+        None
+    };
+    let update_op: () = match query!(
+        "UPDATE message_keys SET delivered_at = COALESCE(delivered_at, $1), read_at = COALESCE(read_at, $2) WHERE recipient = $3 AND msg_id IN (SELECT msg_id FROM messages WHERE chat_id = $4 AND sender <> $3 AND (published::timestamptz, msg_id) <= ($5::text::timestamptz, $6))",
+        now,
+        read_at,
+        user.username,
+        target.chat_id,
+        target.published,
+        target.msg_id
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(_f) => {},
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(update_op)
+}
+
+// used.
+pub async fn get_sent_message_keys(
+    chat_id: &str,
+    sender: &str,
+    pool: &Pool<Postgres>
+) -> Result<Vec<MessageKey>, YokaiErr>{
+    let objects: Vec<MessageKey> = match query_as!(
+        MessageKey,
+        "SELECT * FROM message_keys WHERE recipient <> $2 AND msg_id IN (SELECT msg_id FROM messages WHERE chat_id = $1 AND sender = $2)",
+        chat_id,
+        sender
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<MessageKey>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn message_participants(
+    msg_id: &str,
+    user: &str,
+    pool: &Pool<Postgres>
+) -> Result<Vec<String>, YokaiErr>{
+    let msg: Message = match get_message_by_id(
+        msg_id,
+        pool
+    ).await {
+        Ok(msg) => msg,
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let chat: Chat = match get_chat_by_id(
+        &msg.chat_id,
+        pool
+    ).await {
+        Ok(chat) => chat,
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let participants: Vec<String> = match active_participants(
+        &chat,
+        pool
+    ).await {
+        Ok(participants) => participants,
+        Err(e) => return Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if participants.iter().any(|p| p == user){
+        Ok(participants)
+    }
+    else {
+        Err::<Vec<String>, YokaiErr>(
+            YokaiErr::new("User is not a member of this chat.")
+        )
+    }
+}
+
+// used.
+pub async fn add_reaction(
+    msg_id: &str,
+    user: &str,
+    emoji: &str,
+    pool: &Pool<Postgres>
+) -> Result<bool, YokaiErr>{
+    if !check_emoji(emoji){
+        return Err::<bool, YokaiErr>(
+            YokaiErr::new("Reaction is not a valid emoji.")
+        );
+    }
+    let msg: Message = match get_message_by_id(
+        msg_id,
+        pool
+    ).await {
+        Ok(msg) => msg,
+        Err(e) => return Err::<bool, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    if msg.deleted {
+        return Err::<bool, YokaiErr>(
+            YokaiErr::new("Cannot react to a deleted message.")
+        );
+    }
+    let reaction: MessageReaction = MessageReaction{
+        msg_id: msg.msg_id,
+        username: user.to_string(),
+        emoji: emoji.to_string(),
+        reacted: rfc2282()
+    };
+    let added: bool = match query!(
+        "INSERT INTO message_reactions (msg_id, username, emoji, reacted) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+        reaction.msg_id,
+        reaction.username,
+        reaction.emoji,
+        reaction.reacted,
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(feedback) => feedback.rows_affected() == 1,
+        Err(e) => return Err::<bool, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(added)
+}
+
+// used.
+pub async fn remove_reaction(
+    msg_id: &str,
+    user: &str,
+    emoji: &str,
+    pool: &Pool<Postgres>
+) -> Result<bool, YokaiErr>{
+    let removed: bool = match query!(
+        "DELETE FROM message_reactions WHERE msg_id = $1 AND username = $2 AND emoji = $3",
+        msg_id,
+        user,
+        emoji
+    )
+        .execute(pool)
+        .await
+    {
+        Ok(feedback) => feedback.rows_affected() == 1,
+        Err(e) => return Err::<bool, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(removed)
+}
+
+// used.
+pub async fn get_chat_reactions(
+    chat_id: &str,
+    pool: &Pool<Postgres>
+) -> Result<Vec<MessageReaction>, YokaiErr>{
+    let objects: Vec<MessageReaction> = match query_as!(
+        MessageReaction,
+        "SELECT * FROM message_reactions WHERE msg_id IN (SELECT msg_id FROM messages WHERE chat_id = $1)",
+        chat_id
+    )
+        .fetch_all(pool)
+        .await 
+    {
+        Ok(objects) => objects,
+        Err(e) => return Err::<Vec<MessageReaction>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(objects)
+}
+
+// used.
+pub async fn render_messages(
+    chat_id: &str,
+    messages: Vec<Message>,
+    user: &str,
+    pool: &Pool<Postgres>
+) -> Result<Vec<ChatMessage>, YokaiErr>{
+    let reply_counts: HashMap<String, i64> = match get_reply_counts(
+        chat_id,
+        pool
+    ).await {
+        Ok(reply_counts) => reply_counts,
+        Err(e) => return Err::<Vec<ChatMessage>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let reactions: Vec<MessageReaction> = match get_chat_reactions(
+        chat_id,
+        pool
+    ).await {
+        Ok(reactions) => reactions,
+        Err(e) => return Err::<Vec<ChatMessage>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let sent_keys: Vec<MessageKey> = match get_sent_message_keys(
+        chat_id,
+        user,
+        pool
+    ).await {
+        Ok(sent_keys) => sent_keys,
+        Err(e) => return Err::<Vec<ChatMessage>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut chat_messages: Vec<ChatMessage> = Vec::new();
+    for message in messages {
+        let content: String = if message.deleted {
+            String::new()
+        }
+        else if message.is_system {
+            message.content.clone()
+        }
+        else {
+            match get_message_key(
+                &message.msg_id,
+                user,
+                pool
+            ).await {
+                Ok(key) => key.content,
+                Err(_e) => continue
+            }
+        };
+        let reply_count: i64 = reply_counts
+            .get(&message.msg_id)
+            .copied()
+            .unwrap_or(0);
+        let message_reactions: Vec<ReactionSummary> = summarize_reactions(
+            &message.msg_id,
+            &reactions
+        );
+        let receipts: Vec<Receipt> = sent_keys
+            .iter()
+            .filter(|key| key.msg_id == message.msg_id)
+            .map(
+                |key| Receipt {
+                    username: key.recipient.clone(),
+                    delivered_at: key.delivered_at.clone(),
+                    read_at: key.read_at.clone()
+                }
+            )
+            .collect::<Vec<Receipt>>();
+        let chat_message: ChatMessage = ChatMessage{
+            msg_id: message.msg_id,
+            published: message.published,
+            sender: message.sender,
+            content,
+            attachment: message.attachment,
+            edited: message.edited,
+            deleted: message.deleted,
+            reply_to: message.reply_to,
+            thread_root: message.thread_root,
+            reply_count,
+            reactions: message_reactions,
+            receipts,
+            expires_at: message.expires_at,
+            system: message.is_system
+        };
+        chat_messages.push(chat_message);
+    }
+    Ok(chat_messages)
+}
+
+// used.
+pub fn summarize_reactions(
+    msg_id: &str,
+    reactions: &[MessageReaction]
+) -> Vec<ReactionSummary> {
+    let mut summaries: Vec<ReactionSummary> = Vec::new();
+    for reaction in reactions.iter().filter(|r| r.msg_id == msg_id) {
+        match summaries.iter_mut().find(|s| s.emoji == reaction.emoji) {
+            Some(summary) => {
+                summary.count += 1;
+                summary.users.push(reaction.username.clone());
+            },
+            None => summaries.push(
+                ReactionSummary {
+                    emoji: reaction.emoji.clone(),
+                    count: 1,
+                    users: vec![reaction.username.clone()]
+                }
+            )
+        }
+    }
+    summaries
+}
+
 // used.
 pub async fn get_user_tokens(
     user: &str,
     pool: &Pool<Postgres>
 ) -> Result<Vec<UserAPIToken>, YokaiErr>{
     let obj: User = match get_user_by_id(
-        &user,
+        user,
         pool
     ).await {
         Ok(user_obj) => user_obj,
@@ -28,10 +28,6 @@ impl YokaiErr{
             details: details.to_owned()
         }
     }
-
-    pub fn to_string(self) -> String {
-        self.details.to_string()
-    }
 }
 
 impl Error for YokaiErr {
@@ -52,7 +48,7 @@ impl Display for YokaiErr{
 impl error::ResponseError for YokaiErr {
     fn error_response(&self) -> HttpResponse {
         let resp: ErrDetails = ErrDetails{ 
-            details: (*((&self.details).clone())).to_string()
+            details: self.details.clone()
         };
         HttpResponse::Ok().json(resp)
     }
@@ -0,0 +1,116 @@
+/*
+Yokai by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+use std::sync::Mutex;
+use serde::Serialize;
+use super::err::YokaiErr;
+use std::collections::HashMap;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::mpsc::unbounded_channel;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+pub const REACTION_EVENT: &str = "reaction";
+
+pub const MESSAGE_EVENT: &str = "message";
+
+pub struct EventHub {
+    subscribers: Mutex<HashMap<String, Vec<UnboundedSender<String>>>>
+}
+
+impl Default for EventHub {
+    fn default() -> EventHub {
+        EventHub::new()
+    }
+}
+
+impl EventHub {
+
+    pub fn new() -> EventHub {
+        EventHub {
+            subscribers: Mutex::new(HashMap::new())
+        }
+    }
+
+    pub fn subscribe(
+        &self,
+        user: &str
+    ) -> Result<UnboundedReceiver<String>, YokaiErr> {
+        let (sender, receiver) = unbounded_channel::<String>();
+        let mut subscribers = match self.subscribers.lock(){
+            Ok(subscribers) => subscribers,
+            Err(e) => return Err::<UnboundedReceiver<String>, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        subscribers.retain(
+            |_user, senders| {
+                senders.retain(|sender| !sender.is_closed());
+                !senders.is_empty()
+            }
+        );
+        subscribers
+            .entry(user.to_string())
+            .or_default()
+            .push(sender);
+        Ok(receiver)
+    }
+
+    pub fn subscriber_count(
+        &self,
+        user: &str
+    ) -> Result<usize, YokaiErr> {
+        let subscribers = match self.subscribers.lock(){
+            Ok(subscribers) => subscribers,
+            Err(e) => return Err::<usize, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        Ok(subscribers.get(user).map_or(0, |senders| senders.len()))
+    }
+
+    pub fn publish<T: Serialize>(
+        &self,
+        users: &[String],
+        event: &str,
+        payload: &T
+    ) -> Result<(), YokaiErr> {
+        let data: String = match serde_json::to_string(payload){
+            Ok(data) => data,
+            Err(e) => return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let message: String = format!("event: {}\ndata: {}\n\n", event, data);
+        let mut subscribers = match self.subscribers.lock(){
+            Ok(subscribers) => subscribers,
+            Err(e) => return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        for user in users {
+            if let Some(senders) = subscribers.get_mut(user){
+                senders.retain(|sender| sender.send(message.clone()).is_ok());
+                if senders.is_empty(){
+                    subscribers.remove(user);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn disconnect(
+        &self,
+        user: &str
+    ) -> Result<(), YokaiErr> {
+        let mut subscribers = match self.subscribers.lock(){
+            Ok(subscribers) => subscribers,
+            Err(e) => return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        subscribers.remove(user);
+        Ok(())
+    }
+}
@@ -0,0 +1,338 @@
+/*
+Yokai by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+use sqlx::Pool;
+use image::Frame;
+use std::fs::File;
+use image::Limits;
+use std::path::Path;
+use std::fs::rename;
+use std::io::BufReader;
+use std::path::PathBuf;
+use image::ImageReader;
+use image::ImageFormat;
+use image::DynamicImage;
+use image::ImageDecoder;
+use std::fs::remove_file;
+use super::err::YokaiErr;
+use actix_web::web::block;
+use image::AnimationDecoder;
+use super::utils::file_size;
+use sqlx::postgres::Postgres;
+use super::db::set_file_size;
+use super::utils::storage_dir;
+use image::codecs::gif::Repeat;
+use super::models::FileVariant;
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use super::units::RenderedVariant;
+use image::codecs::gif::GifDecoder;
+use image::codecs::gif::GifEncoder;
+use super::db::create_file_variant;
+
+pub const AVATAR_VARIANT: &str = "avatar";
+
+pub const THUMBNAIL_VARIANT: &str = "thumbnail";
+
+pub const MAX_IMAGE_DIMENSION: u32 = 8192;
+
+pub fn variant_size(
+    variant: &str
+) -> Option<u32> {
+    match variant {
+        AVATAR_VARIANT => Some(256),
+        THUMBNAIL_VARIANT => Some(64),
+        _ => None
+    }
+}
+
+pub fn avatar_url(
+    file_id: &str,
+    variant: &str
+) -> String {
+    format!("/avatar/{}/{}", file_id, variant)
+}
+
+pub fn branding_path(
+    file_id: &str
+) -> Result<PathBuf, YokaiErr> {
+    let mut path_buf: PathBuf = match storage_dir("branding"){
+        Ok(path_buf) => path_buf,
+        Err(e) => return Err::<PathBuf, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    path_buf.push(format!("{}.png", file_id));
+    Ok(path_buf)
+}
+
+pub fn render_branding(
+    source: &Path,
+    dest: &Path
+) -> Result<(), YokaiErr> {
+    let img: DynamicImage = match load_image(source){
+        Ok(img) => img,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    match img.save_with_format(dest, ImageFormat::Png){
+        Ok(_f) => Ok(()),
+        Err(e) => Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    }
+}
+
+pub fn load_image(
+    path: &Path
+) -> Result<DynamicImage, YokaiErr> {
+    let reader = match ImageReader::open(path){
+        Ok(reader) => reader,
+        Err(e) => return Err::<DynamicImage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut reader = match reader.with_guessed_format(){
+        Ok(reader) => reader,
+        Err(e) => return Err::<DynamicImage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let supported: bool = matches!(
+        reader.format(),
+        Some(ImageFormat::Png) |
+        Some(ImageFormat::Jpeg) |
+        Some(ImageFormat::Gif) |
+        Some(ImageFormat::WebP)
+    );
+    if !supported {
+        return Err::<DynamicImage, YokaiErr>(
+            YokaiErr::new("Unsupported image format.")
+        );
+    }
+    let mut limits: Limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    reader.limits(limits);
+    let mut decoder = match reader.into_decoder(){
+        Ok(decoder) => decoder,
+        Err(e) => return Err::<DynamicImage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let orientation: Orientation = match decoder.orientation(){
+        Ok(orientation) => orientation,
+        Err(e) => return Err::<DynamicImage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut img: DynamicImage = match DynamicImage::from_decoder(decoder){
+        Ok(img) => img,
+        Err(e) => return Err::<DynamicImage, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    img.apply_orientation(orientation);
+    Ok(img)
+}
+
+pub fn strip_metadata(
+    path: &Path
+) -> Result<(), YokaiErr> {
+    let reader = match ImageReader::open(path){
+        Ok(reader) => reader,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let reader = match reader.with_guessed_format(){
+        Ok(reader) => reader,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let format: ImageFormat = match reader.format(){
+        Some(ImageFormat::Png) => ImageFormat::Png,
+        Some(ImageFormat::Jpeg) => ImageFormat::Jpeg,
+        Some(ImageFormat::WebP) => ImageFormat::WebP,
+        Some(ImageFormat::Gif) => return strip_gif_metadata(path),
+        _ => return Ok(())
+    };
+    let img: DynamicImage = match load_image(path){
+        Ok(img) => img,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let stripped: PathBuf = path.with_extension("stripped");
+    let _: () = match img.save_with_format(stripped.as_path(), format){
+        Ok(_f) => {},
+        Err(e) => {
+            let _ = remove_file(stripped.as_path());
+            return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            );
+        }
+    };
+    match rename(stripped.as_path(), path){
+        Ok(_f) => Ok(()),
+        Err(e) => Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    }
+}
+
+fn strip_gif_metadata(
+    path: &Path
+) -> Result<(), YokaiErr> {
+    let file: File = match File::open(path){
+        Ok(file) => file,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let decoder: GifDecoder<BufReader<File>> = match GifDecoder::new(BufReader::new(file)){
+        Ok(decoder) => decoder,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let frames: Vec<Frame> = match decoder.into_frames().collect_frames(){
+        Ok(frames) => frames,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let stripped: PathBuf = path.with_extension("stripped");
+    let output: File = match File::create(stripped.as_path()){
+        Ok(output) => output,
+        Err(e) => return Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut encoder: GifEncoder<File> = GifEncoder::new(output);
+    let encoded: Result<(), YokaiErr> = match encoder.set_repeat(Repeat::Infinite){
+        Ok(_f) => match encoder.encode_frames(frames){
+            Ok(_f) => Ok(()),
+            Err(e) => Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        },
+        Err(e) => Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    drop(encoder);
+    if let Err(e) = encoded {
+        let _ = remove_file(stripped.as_path());
+        return Err::<(), YokaiErr>(e);
+    }
+    match rename(stripped.as_path(), path){
+        Ok(_f) => Ok(()),
+        Err(e) => Err::<(), YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    }
+}
+
+pub fn render_variants(
+    file_id: &str,
+    source: &Path,
+    dest_dir: &Path
+) -> Result<Vec<RenderedVariant>, YokaiErr> {
+    let img: DynamicImage = match load_image(source){
+        Ok(img) => img,
+        Err(e) => return Err::<Vec<RenderedVariant>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut rendered: Vec<RenderedVariant> = Vec::new();
+    for variant in [AVATAR_VARIANT, THUMBNAIL_VARIANT] {
+        let size: u32 = match variant_size(variant){
+            Some(size) => size,
+            None => return Err::<Vec<RenderedVariant>, YokaiErr>(
+                YokaiErr::new("Unknown image variant.")
+            )
+        };
+        let resized: DynamicImage = img.resize_to_fill(
+            size,
+            size,
+            FilterType::Lanczos3
+        );
+        let mut path_buf: PathBuf = dest_dir.to_path_buf();
+        path_buf.push(format!("{}_{}.png", file_id, variant));
+        let _: () = match resized.save_with_format(
+            path_buf.as_path(),
+            ImageFormat::Png
+        ){
+            Ok(_f) => {},
+            Err(e) => return Err::<Vec<RenderedVariant>, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        rendered.push(
+            RenderedVariant {
+                variant: variant.to_string(),
+                file_path: path_buf.display().to_string()
+            }
+        );
+    }
+    Ok(rendered)
+}
+
+pub async fn create_image_variants(
+    file_id: &str,
+    source: &Path,
+    pool: &Pool<Postgres>
+) -> Result<Vec<FileVariant>, YokaiErr> {
+    let variants_dir: PathBuf = match storage_dir("user_files/variants"){
+        Ok(variants_dir) => variants_dir,
+        Err(e) => return Err::<Vec<FileVariant>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let variant_source: String = file_id.to_string();
+    let source_path: PathBuf = source.to_path_buf();
+    let rendered: Vec<RenderedVariant> = match block(
+        move || match strip_metadata(source_path.as_path()){
+            Ok(_f) => render_variants(
+                &variant_source,
+                source_path.as_path(),
+                variants_dir.as_path()
+            ),
+            Err(e) => Err::<Vec<RenderedVariant>, YokaiErr>(e)
+        }
+    ).await {
+        Ok(Ok(rendered)) => rendered,
+        _ => Vec::new()
+    };
+    let _: () = match set_file_size(
+        file_id,
+        &(file_size(&source.display().to_string()) as i64),
+        pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<Vec<FileVariant>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut variants: Vec<FileVariant> = Vec::new();
+    for variant in rendered {
+        let file_variant: FileVariant = match create_file_variant(
+            file_id,
+            &variant.variant,
+            &variant.file_path,
+            pool
+        ).await {
+            Ok(file_variant) => file_variant,
+            Err(e) => return Err::<Vec<FileVariant>, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        variants.push(file_variant);
+    }
+    Ok(variants)
+}
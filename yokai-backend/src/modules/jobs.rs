@@ -0,0 +1,486 @@
+/*
+Yokai by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+use sqlx::Pool;
+use std::path::Path;
+use std::fs::ReadDir;
+use sqlx::Connection;
+use std::fs::DirEntry;
+use std::fs::Metadata;
+use std::fs::read_dir;
+use sqlx::Transaction;
+use std::path::PathBuf;
+use std::time::Duration;
+use super::models::User;
+use std::fs::remove_file;
+use super::err::YokaiErr;
+use actix_web::rt::spawn;
+use actix_web::web::Data;
+use std::time::SystemTime;
+use super::utils::rfc2282;
+use super::units::AppData;
+use super::models::Message;
+use super::models::UserFile;
+use super::events::EventHub;
+use super::db::get_all_files;
+use sqlx::postgres::Postgres;
+use super::db::write_message;
+use super::db::get_user_by_id;
+use super::db::delete_message;
+use std::collections::HashSet;
+use super::utils::storage_dir;
+use super::models::FileVariant;
+use super::db::delete_user_file;
+use super::db::set_file_missing;
+use super::utils::seconds_since;
+use super::db::attachment_in_use;
+use super::models::UploadSession;
+use super::utils::decrypt_message;
+use actix_web::rt::time::interval;
+use super::db::get_upload_sessions;
+use super::models::ScheduledMessage;
+use super::db::get_expiring_messages;
+use super::db::get_all_file_variants;
+use super::db::delete_upload_session;
+use super::api::publish_message_event;
+use super::db::get_scheduled_messages;
+use super::db::claim_scheduled_message;
+use super::db::record_delivery_failure;
+use super::db::delete_scheduled_message;
+use super::responses::StorageReportResponse;
+
+pub const JOB_INTERVAL: u64 = 300;
+
+pub const UPLOAD_SESSION_TTL: i64 = 86400;
+
+pub const ORPHAN_GRACE_PERIOD: u64 = 86400;
+
+pub const SCHEDULER_INTERVAL: u64 = 5;
+
+pub const MAX_DELIVERY_ATTEMPTS: i32 = 5;
+
+pub const DELIVERY_RETRY_DELAY: i64 = 60;
+
+pub async fn clean_upload_sessions(
+    pool: &Pool<Postgres>
+) -> Result<usize, YokaiErr> {
+    let sessions: Vec<UploadSession> = match get_upload_sessions(pool).await {
+        Ok(sessions) => sessions,
+        Err(e) => return Err::<usize, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut removed: usize = 0;
+    for session in sessions {
+        let idle: i64 = match seconds_since(&session.updated){
+            Ok(idle) => idle,
+            Err(e) => return Err::<usize, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        if idle > UPLOAD_SESSION_TTL {
+            let _del_op: () = match delete_upload_session(
+                &session.session_id,
+                pool
+            ).await {
+                Ok(_f) => {},
+                Err(e) => return Err::<usize, YokaiErr>(
+                    YokaiErr::new(&e.to_string())
+                )
+            };
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+pub async fn reap_expired_messages(
+    pool: &Pool<Postgres>
+) -> Result<usize, YokaiErr> {
+    let messages: Vec<Message> = match get_expiring_messages(pool).await {
+        Ok(messages) => messages,
+        Err(e) => return Err::<usize, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut removed: usize = 0;
+    for message in messages {
+        let expires_at: String = match message.expires_at {
+            Some(expires_at) => expires_at,
+            None => continue
+        };
+        let overdue: i64 = match seconds_since(&expires_at){
+            Ok(overdue) => overdue,
+            Err(e) => {
+                log_job_error("reap_expired_messages", &e);
+                continue;
+            }
+        };
+        if overdue < 0 {
+            continue;
+        }
+        let _del_op: () = match delete_message(
+            &message.msg_id,
+            pool
+        ).await {
+            Ok(_f) => {},
+            Err(e) => {
+                log_job_error("reap_expired_messages", &e);
+                continue;
+            }
+        };
+        removed += 1;
+        let file_id: String = match message.attachment {
+            Some(file_id) => file_id,
+            None => continue
+        };
+        let in_use: bool = match attachment_in_use(&file_id, pool).await {
+            Ok(in_use) => in_use,
+            Err(e) => {
+                log_job_error("reap_expired_messages", &e);
+                continue;
+            }
+        };
+        if !in_use &&
+           let Err(e) = delete_user_file(&file_id, pool).await
+        {
+            log_job_error("reap_expired_messages", &e);
+        }
+    }
+    Ok(removed)
+}
+
+pub async fn deliver_scheduled_message(
+    scheduled: &ScheduledMessage,
+    pool: &Pool<Postgres>,
+    tx: &mut Transaction<'_, Postgres>
+) -> Result<Message, YokaiErr> {
+    let sender: User = match get_user_by_id(
+        &scheduled.sender,
+        pool
+    ).await {
+        Ok(sender) => sender,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let msg: String = match decrypt_message(
+        &scheduled.content,
+        &sender.private_key
+    ){
+        Ok(msg) => msg,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let message: Message = match write_message(
+        &msg,
+        &sender.username,
+        &scheduled.chat_id,
+        &scheduled.attachment,
+        &scheduled.reply_to,
+        pool,
+        tx
+    ).await {
+        Ok(message) => message,
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _del_op: () = match delete_scheduled_message(
+        &scheduled.schedule_id,
+        tx
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<Message, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(message)
+}
+
+pub async fn deliver_scheduled_messages(
+    events: &EventHub,
+    pool: &Pool<Postgres>
+) -> Result<usize, YokaiErr> {
+    let queued: Vec<ScheduledMessage> = match get_scheduled_messages(pool).await {
+        Ok(queued) => queued,
+        Err(e) => return Err::<usize, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut delivered: usize = 0;
+    for candidate in queued {
+        let mut tx: Transaction<'_, Postgres> = match pool.begin().await {
+            Ok(tx) => tx,
+            Err(e) => return Err::<usize, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let scheduled: ScheduledMessage = match claim_scheduled_message(
+            &candidate.schedule_id,
+            &mut tx
+        ).await {
+            Ok(Some(scheduled)) => scheduled,
+            Ok(None) => continue,
+            Err(e) => return Err::<usize, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let due: i64 = match seconds_since(&scheduled.send_at){
+            Ok(due) => due,
+            Err(e) => {
+                let _: () = match record_delivery_failure(
+                    &scheduled.schedule_id,
+                    &e.to_string(),
+                    &true,
+                    &mut tx
+                ).await {
+                    Ok(_f) => {},
+                    Err(e) => return Err::<usize, YokaiErr>(
+                        YokaiErr::new(&e.to_string())
+                    )
+                };
+                let _commit: () = match tx.commit().await {
+                    Ok(_f) => {},
+                    Err(e) => return Err::<usize, YokaiErr>(
+                        YokaiErr::new(&e.to_string())
+                    )
+                };
+                continue;
+            }
+        };
+        if due < (scheduled.attempts as i64) * DELIVERY_RETRY_DELAY {
+            continue;
+        }
+        let mut attempt: Transaction<'_, Postgres> = match tx.begin().await {
+            Ok(attempt) => attempt,
+            Err(e) => return Err::<usize, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let sent: Option<Message> = match deliver_scheduled_message(
+            &scheduled,
+            pool,
+            &mut attempt
+        ).await {
+            Ok(message) => {
+                let _release: () = match attempt.commit().await {
+                    Ok(_f) => {},
+                    Err(e) => return Err::<usize, YokaiErr>(
+                        YokaiErr::new(&e.to_string())
+                    )
+                };
+                Some(message)
+            },
+            Err(e) => {
+                let _undo: () = match attempt.rollback().await {
+                    Ok(_f) => {},
+                    Err(e) => return Err::<usize, YokaiErr>(
+                        YokaiErr::new(&e.to_string())
+                    )
+                };
+                let failed: bool = scheduled.attempts + 1 >= MAX_DELIVERY_ATTEMPTS;
+                let _: () = match record_delivery_failure(
+                    &scheduled.schedule_id,
+                    &e.to_string(),
+                    &failed,
+                    &mut tx
+                ).await {
+                    Ok(_f) => {},
+                    Err(e) => return Err::<usize, YokaiErr>(
+                        YokaiErr::new(&e.to_string())
+                    )
+                };
+                None
+            }
+        };
+        let _commit: () = match tx.commit().await {
+            Ok(_f) => {},
+            Err(e) => return Err::<usize, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        if let Some(message) = sent {
+            if let Err(e) = publish_message_event(&message, events, pool).await {
+                log_job_error("deliver_scheduled_messages", &e);
+            }
+            delivered += 1;
+        }
+    }
+    Ok(delivered)
+}
+
+pub fn log_job_error(
+    job: &str,
+    err: &YokaiErr
+) {
+    eprintln!("[{}] {}: {}", rfc2282(), job, &err.to_string());
+}
+
+pub fn find_orphans(
+    dir: &Path,
+    known: &HashSet<String>
+) -> Result<Vec<PathBuf>, YokaiErr> {
+    let entries: ReadDir = match read_dir(dir){
+        Ok(entries) => entries,
+        Err(e) => return Err::<Vec<PathBuf>, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut orphans: Vec<PathBuf> = Vec::new();
+    for entry in entries {
+        let entry: DirEntry = match entry {
+            Ok(entry) => entry,
+            Err(e) => return Err::<Vec<PathBuf>, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let metadata: Metadata = match entry.metadata(){
+            Ok(metadata) => metadata,
+            Err(e) => return Err::<Vec<PathBuf>, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        if !metadata.is_file() ||
+           known.contains(&entry.path().display().to_string())
+        {
+            continue;
+        }
+        let age: u64 = match metadata.modified(){
+            Ok(modified) => match SystemTime::now().duration_since(modified){
+                Ok(age) => age.as_secs(),
+                Err(_e) => 0
+            },
+            Err(e) => return Err::<Vec<PathBuf>, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        if age > ORPHAN_GRACE_PERIOD {
+            orphans.push(entry.path());
+        }
+    }
+    Ok(orphans)
+}
+
+pub async fn collect_orphaned_files(
+    dry_run: &bool,
+    pool: &Pool<Postgres>
+) -> Result<StorageReportResponse, YokaiErr> {
+    let files: Vec<UserFile> = match get_all_files(pool).await {
+        Ok(files) => files,
+        Err(e) => return Err::<StorageReportResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let variants: Vec<FileVariant> = match get_all_file_variants(pool).await {
+        Ok(variants) => variants,
+        Err(e) => return Err::<StorageReportResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let sessions: Vec<UploadSession> = match get_upload_sessions(pool).await {
+        Ok(sessions) => sessions,
+        Err(e) => return Err::<StorageReportResponse, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut known: HashSet<String> = HashSet::new();
+    let mut missing_files: Vec<String> = Vec::new();
+    for file in files {
+        let exists: bool = Path::new(&file.file_path).exists();
+        if !exists {
+            missing_files.push(file.file_id.clone());
+        }
+        if !*dry_run && exists == file.missing {
+            let _: () = match set_file_missing(
+                &file.file_id,
+                &!exists,
+                pool
+            ).await {
+                Ok(_f) => {},
+                Err(e) => return Err::<StorageReportResponse, YokaiErr>(
+                    YokaiErr::new(&e.to_string())
+                )
+            };
+        }
+        known.insert(file.file_path);
+    }
+    for variant in variants {
+        known.insert(variant.file_path);
+    }
+    for session in sessions {
+        known.insert(session.file_path);
+    }
+    let mut orphaned_files: Vec<String> = Vec::new();
+    for dir in ["user_files", "user_files/variants", "user_files/partial"] {
+        let dir_path: PathBuf = match storage_dir(dir){
+            Ok(dir_path) => dir_path,
+            Err(e) => return Err::<StorageReportResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let orphans: Vec<PathBuf> = match find_orphans(&dir_path, &known){
+            Ok(orphans) => orphans,
+            Err(e) => return Err::<StorageReportResponse, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        for orphan in orphans {
+            if !*dry_run {
+                let _: () = match remove_file(&orphan){
+                    Ok(_f) => {},
+                    Err(e) => return Err::<StorageReportResponse, YokaiErr>(
+                        YokaiErr::new(&e.to_string())
+                    )
+                };
+            }
+            orphaned_files.push(orphan.display().to_string());
+        }
+    }
+    let report: StorageReportResponse = StorageReportResponse {
+        orphaned_files,
+        missing_files
+    };
+    Ok(report)
+}
+
+pub fn spawn_jobs(
+    data: Data<AppData>
+) {
+    let scheduler_data: Data<AppData> = data.clone();
+    let pool: Pool<Postgres> = data.pool.clone();
+    spawn(
+        async move {
+            let mut ticker = interval(Duration::from_secs(SCHEDULER_INTERVAL));
+            loop {
+                ticker.tick().await;
+                if let Err(e) = deliver_scheduled_messages(
+                    &scheduler_data.events,
+                    &scheduler_data.pool
+                ).await {
+                    log_job_error("deliver_scheduled_messages", &e);
+                }
+            }
+        }
+    );
+    spawn(
+        async move {
+            let mut ticker = interval(Duration::from_secs(JOB_INTERVAL));
+            loop {
+                ticker.tick().await;
+                if let Err(e) = clean_upload_sessions(&pool).await {
+                    log_job_error("clean_upload_sessions", &e);
+                }
+                if let Err(e) = reap_expired_messages(&pool).await {
+                    log_job_error("reap_expired_messages", &e);
+                }
+                if let Err(e) = collect_orphaned_files(&false, &pool).await {
+                    log_job_error("collect_orphaned_files", &e);
+                }
+            }
+        }
+    );
+}
@@ -6,12 +6,18 @@ Licensed under the FSL v1.
 pub mod db;
 pub mod api;
 pub mod err;
+pub mod jobs;
 #[cfg(test)]
 pub mod tests;
 pub mod utils;
 pub mod units;
+pub mod roles;
+pub mod events;
 pub mod config;
+pub mod images;
 pub mod models;
 pub mod runner;
+pub mod scanner;
+pub mod presence;
 pub mod payloads;
 pub mod responses;
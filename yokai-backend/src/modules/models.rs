@@ -9,7 +9,6 @@ use sqlx::FromRow;
 pub struct User {
     pub username: String,
     pub password: String,
-    pub is_admin: bool,
     pub public_key: String,
     pub private_key: String,
     pub description: String,
@@ -17,7 +16,15 @@ pub struct User {
     pub primary_color: String,
     pub tertiary_color: String,
     pub secondary_color: String,
-    pub user_pfp_id: Option<String>
+    pub user_pfp_id: Option<String>,
+    pub read_receipts: bool,
+    pub last_seen_visibility: String,
+    pub require_approval: bool,
+    pub suspended_at: Option<String>,
+    pub suspended_until: Option<String>,
+    pub suspension_reason: Option<String>,
+    pub user_role: String,
+    pub last_login: Option<String>
 }
 
 #[derive(FromRow, Clone)]
@@ -25,7 +32,11 @@ pub struct Chat{
     pub chat_id: String,
     pub started: String,
     pub sender: String,
-    pub receiver: String,
+    pub receiver: Option<String>,
+    pub is_group: bool,
+    pub title: Option<String>,
+    pub message_ttl: Option<i64>,
+    pub pending: bool
 }
 
 #[derive(FromRow, Clone)]
@@ -34,16 +45,25 @@ pub struct Message{
     pub published: String,
     pub content: String,
     pub sender: String,
-    pub receiver: String,
+    pub receiver: Option<String>,
     pub attachment: Option<String>,
-    pub chat_id: String
+    pub chat_id: String,
+    pub edited: Option<String>,
+    pub deleted: bool,
+    pub reply_to: Option<String>,
+    pub thread_root: Option<String>,
+    pub expires_at: Option<String>,
+    pub is_system: bool
 }
 
 #[derive(FromRow, Clone)]
 pub struct UserFile{
     pub file_id: String,
     pub file_path: String,
-    pub file_owner: String
+    pub file_owner: String,
+    pub missing: bool,
+    pub scan_verdict: Option<String>,
+    pub file_size: i64
 }
 
 #[derive(FromRow, Clone)]
@@ -51,7 +71,14 @@ pub struct HostInformation{
     pub hostname: String,
     pub primary_color: String,
     pub secondary_color: String,
-    pub tertiary_color: String
+    pub tertiary_color: String,
+    pub instance_name: Option<String>,
+    pub description: Option<String>,
+    pub logo_file_id: Option<String>,
+    pub favicon_file_id: Option<String>,
+    pub terms: Option<String>,
+    pub privacy_policy: Option<String>,
+    pub contact_address: Option<String>
 }
 
 #[derive(FromRow, Clone)]
@@ -66,3 +93,132 @@ pub struct UserAPIToken {
     pub token: String,
     pub owner: String
 }
+
+#[derive(FromRow, Clone)]
+pub struct FileVariant{
+    pub variant_id: String,
+    pub file_id: String,
+    pub variant: String,
+    pub file_path: String
+}
+
+#[derive(FromRow, Clone)]
+pub struct UploadSession{
+    pub session_id: String,
+    pub owner: String,
+    pub file_name: String,
+    pub file_path: String,
+    pub total_size: i64,
+    pub received: i64,
+    pub checksum: String,
+    pub created: String,
+    pub updated: String
+}
+
+#[derive(FromRow, Clone)]
+pub struct ChatMember{
+    pub chat_id: String,
+    pub username: String,
+    pub member_role: String,
+    pub joined: String,
+    pub pending: bool,
+    pub invited_by: Option<String>
+}
+
+#[derive(FromRow, Clone)]
+pub struct MessageKey{
+    pub msg_id: String,
+    pub recipient: String,
+    pub content: String,
+    pub delivered_at: Option<String>,
+    pub read_at: Option<String>
+}
+
+#[derive(FromRow, Clone)]
+pub struct MessageEdit{
+    pub edit_id: String,
+    pub msg_id: String,
+    pub recipient: String,
+    pub content: String,
+    pub edited: String
+}
+
+#[derive(FromRow, Clone)]
+pub struct MessageReaction{
+    pub msg_id: String,
+    pub username: String,
+    pub emoji: String,
+    pub reacted: String
+}
+
+#[derive(FromRow, Clone)]
+pub struct ScheduledMessage{
+    pub schedule_id: String,
+    pub chat_id: String,
+    pub sender: String,
+    pub content: String,
+    pub attachment: Option<String>,
+    pub reply_to: Option<String>,
+    pub send_at: String,
+    pub created: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub failed: bool
+}
+
+#[derive(FromRow, Clone)]
+pub struct PinnedMessage{
+    pub chat_id: String,
+    pub msg_id: String,
+    pub pinned_by: String,
+    pub pinned: String
+}
+
+#[derive(FromRow, Clone)]
+pub struct StarredMessage{
+    pub username: String,
+    pub msg_id: String,
+    pub starred: String
+}
+
+#[derive(FromRow, Clone)]
+pub struct ChatSetting{
+    pub chat_id: String,
+    pub username: String,
+    pub archived: bool,
+    pub muted_until: Option<String>,
+    pub pinned: bool,
+    pub nickname: Option<String>
+}
+
+#[derive(FromRow, Clone)]
+pub struct UserBlock{
+    pub blocker: String,
+    pub blocked: String,
+    pub created: String
+}
+
+#[derive(FromRow, Clone)]
+pub struct Report{
+    pub report_id: String,
+    pub reporter: String,
+    pub reported_user: String,
+    pub msg_id: Option<String>,
+    pub reason: String,
+    pub disclosed: Option<String>,
+    pub status: String,
+    pub created: String,
+    pub resolved: Option<String>,
+    pub resolved_by: Option<String>
+}
+
+#[derive(FromRow, Clone)]
+pub struct AuditEntry{
+    pub seq: i64,
+    pub actor: String,
+    pub action: String,
+    pub target: Option<String>,
+    pub details: Option<String>,
+    pub ip: Option<String>,
+    pub created: String
+}
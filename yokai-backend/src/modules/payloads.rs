@@ -74,9 +74,11 @@ pub struct ChatCreatePayload{
 pub struct SendMessagePayload{
     pub msg: String,
     pub api_token: String,
-    pub receiver: String,
+    pub receiver: Option<String>,
     pub chat_id: String,
-    pub attachment: Option<String>
+    pub attachment: Option<String>,
+    pub reply_to: Option<String>,
+    pub send_at: Option<String>
 }
 
 #[derive(Deserialize)]
@@ -84,3 +86,213 @@ pub struct DecryptionPayload{
     pub api_token: String,
     pub message: String
 }
+
+#[derive(Deserialize)]
+pub struct UploadCreatePayload{
+    pub api_token: String,
+    pub name: String,
+    pub total_size: i64,
+    pub checksum: String
+}
+
+#[derive(Deserialize)]
+pub struct UploadSessionPayload{
+    pub api_token: String,
+    pub session_id: String
+}
+
+#[derive(Deserialize)]
+pub struct DownloadQuery{
+    pub expires: i64,
+    pub signature: String
+}
+
+#[derive(Deserialize)]
+pub struct ChatPayload{
+    pub api_token: String,
+    pub chat_id: String
+}
+
+#[derive(Deserialize)]
+pub struct GroupCreatePayload{
+    pub api_token: String,
+    pub title: String,
+    pub members: Vec<String>
+}
+
+#[derive(Deserialize)]
+pub struct GroupMemberPayload{
+    pub api_token: String,
+    pub chat_id: String,
+    pub username: String
+}
+
+#[derive(Deserialize)]
+pub struct GroupRolePayload{
+    pub api_token: String,
+    pub chat_id: String,
+    pub username: String,
+    pub role: String
+}
+
+#[derive(Deserialize)]
+pub struct MessagePayload{
+    pub api_token: String,
+    pub msg_id: String
+}
+
+#[derive(Deserialize)]
+pub struct EditMessagePayload{
+    pub api_token: String,
+    pub msg_id: String,
+    pub msg: String
+}
+
+#[derive(Deserialize)]
+pub struct ReactionPayload{
+    pub api_token: String,
+    pub msg_id: String,
+    pub emoji: String
+}
+
+#[derive(Deserialize)]
+pub struct ReadPayload{
+    pub api_token: String,
+    pub chat_id: String,
+    pub msg_id: String
+}
+
+#[derive(Deserialize)]
+pub struct TogglePayload{
+    pub api_token: String,
+    pub enabled: bool
+}
+
+#[derive(Deserialize)]
+pub struct UserPayload{
+    pub api_token: String,
+    pub username: String
+}
+
+#[derive(Deserialize)]
+pub struct PresencePayload{
+    pub api_token: String,
+    pub status: String
+}
+
+#[derive(Deserialize)]
+pub struct TypingPayload{
+    pub api_token: String,
+    pub chat_id: String,
+    pub typing: bool
+}
+
+#[derive(Deserialize)]
+pub struct ChatTimerPayload{
+    pub api_token: String,
+    pub chat_id: String,
+    pub ttl: Option<i64>
+}
+
+#[derive(Deserialize)]
+pub struct ScheduledPayload{
+    pub api_token: String,
+    pub schedule_id: String
+}
+
+#[derive(Deserialize)]
+pub struct ChatListPayload{
+    pub api_token: String,
+    pub archived: Option<bool>
+}
+
+#[derive(Deserialize)]
+pub struct ChatTogglePayload{
+    pub api_token: String,
+    pub chat_id: String,
+    pub enabled: bool
+}
+
+#[derive(Deserialize)]
+pub struct ChatMutePayload{
+    pub api_token: String,
+    pub chat_id: String,
+    pub muted_until: Option<String>
+}
+
+#[derive(Deserialize)]
+pub struct ChatNicknamePayload{
+    pub api_token: String,
+    pub chat_id: String,
+    pub nickname: Option<String>
+}
+
+#[derive(Deserialize)]
+pub struct ChatDeclinePayload{
+    pub api_token: String,
+    pub chat_id: String,
+    pub block: bool
+}
+
+#[derive(Deserialize)]
+pub struct ReportUserPayload{
+    pub api_token: String,
+    pub username: String,
+    pub reason: String
+}
+
+#[derive(Deserialize)]
+pub struct ReportMessagePayload{
+    pub api_token: String,
+    pub msg_id: String,
+    pub reason: String,
+    pub disclose: bool
+}
+
+#[derive(Deserialize)]
+pub struct ReportQueuePayload{
+    pub api_token: String,
+    pub status: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>
+}
+
+#[derive(Deserialize)]
+pub struct ResolveReportPayload{
+    pub api_token: String,
+    pub report_id: String,
+    pub action: String,
+    pub note: Option<String>
+}
+
+#[derive(Deserialize)]
+pub struct SuspendUserPayload{
+    pub api_token: String,
+    pub username: String,
+    pub reason: String,
+    pub until: Option<String>
+}
+
+#[derive(Deserialize)]
+pub struct UserRolePayload{
+    pub api_token: String,
+    pub username: String,
+    pub role: String
+}
+
+#[derive(Deserialize)]
+pub struct AuditQueryPayload{
+    pub api_token: String,
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>
+}
+
+#[derive(Deserialize)]
+pub struct UserDirectoryPayload{
+    pub api_token: String,
+    pub search: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>
+}
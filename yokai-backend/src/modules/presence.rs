@@ -0,0 +1,225 @@
+/*
+Yokai by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+use chrono::Utc;
+use std::sync::Mutex;
+use chrono::DateTime;
+use super::models::User;
+use super::err::YokaiErr;
+use std::collections::HashMap;
+use super::responses::UserPresence;
+
+pub const PRESENCE_TTL: i64 = 60;
+
+pub const TYPING_TTL: i64 = 10;
+
+pub const ONLINE_STATUS: &str = "online";
+
+pub const AWAY_STATUS: &str = "away";
+
+pub const OFFLINE_STATUS: &str = "offline";
+
+pub const TYPING_EVENT: &str = "typing";
+
+pub const VISIBLE_TO_EVERYONE: &str = "everyone";
+
+pub const VISIBLE_TO_CONTACTS: &str = "contacts";
+
+pub const VISIBLE_TO_NOBODY: &str = "nobody";
+
+pub fn can_see_presence(
+    viewer: &str,
+    subject: &User,
+    is_contact: &bool
+) -> bool {
+    if viewer == subject.username {
+        return true;
+    }
+    match subject.last_seen_visibility.as_str() {
+        VISIBLE_TO_EVERYONE => true,
+        VISIBLE_TO_CONTACTS => *is_contact,
+        _ => false
+    }
+}
+
+pub struct PresenceState {
+    pub status: String,
+    pub last_active: DateTime<Utc>
+}
+
+pub struct PresenceStore {
+    presence: Mutex<HashMap<String, PresenceState>>,
+    typing: Mutex<HashMap<String, HashMap<String, DateTime<Utc>>>>
+}
+
+impl Default for PresenceStore {
+    fn default() -> PresenceStore {
+        PresenceStore::new()
+    }
+}
+
+impl PresenceStore {
+
+    pub fn new() -> PresenceStore {
+        PresenceStore {
+            presence: Mutex::new(HashMap::new()),
+            typing: Mutex::new(HashMap::new())
+        }
+    }
+
+    pub fn touch(
+        &self,
+        user: &str,
+        status: &str
+    ) -> Result<(), YokaiErr> {
+        if status != ONLINE_STATUS && status != AWAY_STATUS {
+            return Err::<(), YokaiErr>(
+                YokaiErr::new("Status must be either online or away.")
+            );
+        }
+        let mut presence = match self.presence.lock(){
+            Ok(presence) => presence,
+            Err(e) => return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        presence.insert(
+            user.to_string(),
+            PresenceState {
+                status: status.to_string(),
+                last_active: Utc::now()
+            }
+        );
+        Ok(())
+    }
+
+    pub fn status(
+        &self,
+        user: &str
+    ) -> Result<(String, Option<String>), YokaiErr> {
+        let presence = match self.presence.lock(){
+            Ok(presence) => presence,
+            Err(e) => return Err::<(String, Option<String>), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let result: (String, Option<String>) = match presence.get(user){
+            Some(state) => {
+                let idle: i64 = (Utc::now() - state.last_active).num_seconds();
+                let status: String = if idle > PRESENCE_TTL {
+                    OFFLINE_STATUS.to_string()
+                }
+                else {
+                    state.status.clone()
+                };
+                (status, Some(state.last_active.to_rfc2822()))
+            },
+            None => (OFFLINE_STATUS.to_string(), None)
+        };
+        Ok(result)
+    }
+
+    pub fn set_typing(
+        &self,
+        chat_id: &str,
+        user: &str,
+        typing: &bool
+    ) -> Result<(), YokaiErr> {
+        let mut chats = match self.typing.lock(){
+            Ok(chats) => chats,
+            Err(e) => return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let typers: &mut HashMap<String, DateTime<Utc>> = chats
+            .entry(chat_id.to_string())
+            .or_default();
+        if *typing {
+            typers.insert(user.to_string(), Utc::now());
+        }
+        else {
+            typers.remove(user);
+        }
+        typers.retain(|_user, started| (Utc::now() - *started).num_seconds() <= TYPING_TTL);
+        if typers.is_empty(){
+            chats.remove(chat_id);
+        }
+        Ok(())
+    }
+
+    pub fn typers(
+        &self,
+        chat_id: &str
+    ) -> Result<Vec<String>, YokaiErr> {
+        let chats = match self.typing.lock(){
+            Ok(chats) => chats,
+            Err(e) => return Err::<Vec<String>, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let typers: Vec<String> = match chats.get(chat_id){
+            Some(typers) => typers
+                .iter()
+                .filter(|(_user, started)| (Utc::now() - **started).num_seconds() <= TYPING_TTL)
+                .map(|(user, _started)| user.clone())
+                .collect::<Vec<String>>(),
+            None => Vec::new()
+        };
+        Ok(typers)
+    }
+
+    pub fn user_presence(
+        &self,
+        subject: &User,
+        visible: &bool
+    ) -> Result<UserPresence, YokaiErr> {
+        if !*visible {
+            return Ok(
+                UserPresence {
+                    username: subject.username.clone(),
+                    status: None,
+                    last_seen: None
+                }
+            );
+        }
+        let (status, last_seen) = match self.status(&subject.username){
+            Ok(state) => state,
+            Err(e) => return Err::<UserPresence, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        Ok(
+            UserPresence {
+                username: subject.username.clone(),
+                status: Some(status),
+                last_seen
+            }
+        )
+    }
+
+    pub fn forget(
+        &self,
+        user: &str
+    ) -> Result<(), YokaiErr> {
+        let mut presence = match self.presence.lock(){
+            Ok(presence) => presence,
+            Err(e) => return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        presence.remove(user);
+        let mut chats = match self.typing.lock(){
+            Ok(chats) => chats,
+            Err(e) => return Err::<(), YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        for typers in chats.values_mut() {
+            typers.remove(user);
+        }
+        chats.retain(|_chat_id, typers| !typers.is_empty());
+        Ok(())
+    }
+}
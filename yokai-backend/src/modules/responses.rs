@@ -35,7 +35,8 @@ pub struct UserContact {
     pub username: String,
     pub description: String,
     pub display_name: String,
-    pub pfp_url: Option<String>
+    pub pfp_url: Option<String>,
+    pub available: bool
 }
 
 #[derive(Serialize)]
@@ -57,3 +58,328 @@ pub struct ListResponse{
 pub struct DecryptionResponse{
     pub message: String
 }
+
+#[derive(Serialize)]
+pub struct UploadSessionResponse{
+    pub session_id: String,
+    pub offset: i64,
+    pub total_size: i64
+}
+
+#[derive(Serialize)]
+pub struct FileIdResponse{
+    pub file_id: String
+}
+
+#[derive(Serialize)]
+pub struct FileLinkResponse{
+    pub file_url: String,
+    pub expires: i64
+}
+
+#[derive(Serialize)]
+pub struct StorageReportResponse{
+    pub orphaned_files: Vec<String>,
+    pub missing_files: Vec<String>
+}
+
+#[derive(Serialize)]
+pub struct ChatIdResponse{
+    pub chat_id: String
+}
+
+#[derive(Serialize)]
+pub struct ChatSummary{
+    pub chat_id: String,
+    pub is_group: bool,
+    pub title: Option<String>,
+    pub participants: Vec<String>,
+    pub archived: bool,
+    pub muted: bool,
+    pub muted_until: Option<String>,
+    pub pinned: bool,
+    pub nickname: Option<String>
+}
+
+#[derive(Serialize)]
+pub struct ChatsResponse{
+    pub chats: Vec<ChatSummary>
+}
+
+#[derive(Serialize)]
+pub struct GroupMember{
+    pub username: String,
+    pub role: String,
+    pub joined: String
+}
+
+#[derive(Serialize)]
+pub struct GroupMembersResponse{
+    pub members: Vec<GroupMember>
+}
+
+#[derive(Serialize)]
+pub struct ChatMessage{
+    pub msg_id: String,
+    pub published: String,
+    pub sender: String,
+    pub content: String,
+    pub attachment: Option<String>,
+    pub edited: Option<String>,
+    pub deleted: bool,
+    pub reply_to: Option<String>,
+    pub thread_root: Option<String>,
+    pub reply_count: i64,
+    pub reactions: Vec<ReactionSummary>,
+    pub receipts: Vec<Receipt>,
+    pub expires_at: Option<String>,
+    pub system: bool
+}
+
+#[derive(Serialize)]
+pub struct Receipt{
+    pub username: String,
+    pub delivered_at: Option<String>,
+    pub read_at: Option<String>
+}
+
+#[derive(Serialize)]
+pub struct ReactionSummary{
+    pub emoji: String,
+    pub count: usize,
+    pub users: Vec<String>
+}
+
+#[derive(Serialize)]
+pub struct ChatMessagesResponse{
+    pub messages: Vec<ChatMessage>
+}
+
+#[derive(Serialize)]
+pub struct MessageRevision{
+    pub content: String,
+    pub edited: String
+}
+
+#[derive(Serialize)]
+pub struct MessageHistoryResponse{
+    pub revisions: Vec<MessageRevision>
+}
+
+#[derive(Serialize)]
+pub struct MessageEvent{
+    pub chat_id: String,
+    pub msg_id: String,
+    pub sender: String,
+    pub published: String
+}
+
+#[derive(Serialize)]
+pub struct ReactionEvent{
+    pub chat_id: String,
+    pub msg_id: String,
+    pub username: String,
+    pub emoji: String,
+    pub added: bool
+}
+
+#[derive(Serialize)]
+pub struct UserPresence{
+    pub username: String,
+    pub status: Option<String>,
+    pub last_seen: Option<String>
+}
+
+#[derive(Serialize)]
+pub struct PresenceResponse{
+    pub presence: Vec<UserPresence>
+}
+
+#[derive(Serialize)]
+pub struct TypingEvent{
+    pub chat_id: String,
+    pub username: String,
+    pub typing: bool
+}
+
+#[derive(Serialize)]
+pub struct TypersResponse{
+    pub usernames: Vec<String>
+}
+
+#[derive(Serialize)]
+pub struct ScheduledIdResponse{
+    pub schedule_id: String
+}
+
+#[derive(Serialize)]
+pub struct ScheduledSummary{
+    pub schedule_id: String,
+    pub chat_id: String,
+    pub content: String,
+    pub attachment: Option<String>,
+    pub reply_to: Option<String>,
+    pub send_at: String,
+    pub created: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub failed: bool
+}
+
+#[derive(Serialize)]
+pub struct ScheduledMessagesResponse{
+    pub messages: Vec<ScheduledSummary>
+}
+
+#[derive(Serialize)]
+pub struct PinSummary{
+    pub pinned_by: String,
+    pub pinned: String,
+    pub message: ChatMessage
+}
+
+#[derive(Serialize)]
+pub struct PinsResponse{
+    pub pins: Vec<PinSummary>
+}
+
+#[derive(Serialize)]
+pub struct StarSummary{
+    pub chat_id: String,
+    pub starred: String,
+    pub message: ChatMessage
+}
+
+#[derive(Serialize)]
+pub struct StarsResponse{
+    pub stars: Vec<StarSummary>
+}
+
+#[derive(Serialize)]
+pub struct BlockSummary{
+    pub username: String,
+    pub created: String
+}
+
+#[derive(Serialize)]
+pub struct BlocksResponse{
+    pub blocks: Vec<BlockSummary>
+}
+
+#[derive(Serialize)]
+pub struct ChatRequest{
+    pub chat_id: String,
+    pub sender: String,
+    pub started: String,
+    pub held: usize
+}
+
+#[derive(Serialize)]
+pub struct ChatRequestsResponse{
+    pub requests: Vec<ChatRequest>
+}
+
+#[derive(Serialize)]
+pub struct ReportIdResponse{
+    pub report_id: String
+}
+
+#[derive(Serialize)]
+pub struct ReportSummary{
+    pub report_id: String,
+    pub reporter: String,
+    pub reported_user: String,
+    pub msg_id: Option<String>,
+    pub reason: String,
+    pub disclosed: Option<String>,
+    pub status: String,
+    pub created: String,
+    pub resolved: Option<String>,
+    pub resolved_by: Option<String>
+}
+
+#[derive(Serialize)]
+pub struct ReportsResponse{
+    pub reports: Vec<ReportSummary>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64
+}
+
+#[derive(Serialize)]
+pub struct AuditEntrySummary{
+    pub seq: i64,
+    pub actor: String,
+    pub action: String,
+    pub target: Option<String>,
+    pub details: Option<String>,
+    pub ip: Option<String>,
+    pub created: String
+}
+
+#[derive(Serialize)]
+pub struct AuditLogResponse{
+    pub entries: Vec<AuditEntrySummary>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64
+}
+
+#[derive(Serialize)]
+pub struct DirectoryUser{
+    pub username: String,
+    pub display_name: String,
+    pub user_role: String,
+    pub suspended: bool,
+    pub last_login: Option<String>
+}
+
+#[derive(Serialize)]
+pub struct UserDirectoryResponse{
+    pub users: Vec<DirectoryUser>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64
+}
+
+#[derive(Serialize)]
+pub struct UserStatsResponse{
+    pub username: String,
+    pub chat_count: usize,
+    pub message_count: i64,
+    pub storage_used: u64,
+    pub last_login: Option<String>
+}
+
+#[derive(Serialize)]
+pub struct InstanceStatsResponse{
+    pub users: i64,
+    pub suspended_users: usize,
+    pub direct_chats: i64,
+    pub group_chats: i64,
+    pub messages: i64,
+    pub files: usize,
+    pub storage_used: u64
+}
+
+#[derive(Serialize)]
+pub struct InstanceInfoResponse{
+    pub hostname: String,
+    pub primary_color: String,
+    pub secondary_color: String,
+    pub tertiary_color: String,
+    pub instance_name: Option<String>,
+    pub description: Option<String>,
+    pub logo_url: Option<String>,
+    pub favicon_url: Option<String>,
+    pub terms: Option<String>,
+    pub privacy_policy: Option<String>,
+    pub contact_address: Option<String>,
+    pub version: String,
+    pub registration_mode: String,
+    pub max_message_bytes: usize,
+    pub max_upload_size: i64,
+    pub upload_chunk_limit: usize,
+    pub edit_window: i64,
+    pub encryption_schemes: Vec<String>
+}
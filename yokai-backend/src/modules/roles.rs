@@ -0,0 +1,62 @@
+/*
+Yokai by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+use super::models::User;
+
+pub const ROLE_ADMIN: &str = "admin";
+
+pub const ROLE_MODERATOR: &str = "moderator";
+
+pub const ROLE_USER: &str = "user";
+
+pub enum Permission {
+    ManageInvites,
+    ManageHost,
+    ManageUsers,
+    ManageRoles,
+    ViewStorage,
+    ViewAudit,
+    ViewUsers,
+    ReviewReports,
+    SuspendUsers
+}
+
+pub fn is_valid_role(
+    role: &str
+) -> bool {
+    role == ROLE_ADMIN || role == ROLE_MODERATOR || role == ROLE_USER
+}
+
+pub fn has_permission(
+    user: &User,
+    permission: &Permission
+) -> bool {
+    match user.user_role.as_str() {
+        ROLE_ADMIN => true,
+        ROLE_MODERATOR => matches!(
+            permission,
+            Permission::ReviewReports |
+            Permission::SuspendUsers
+        ),
+        _ => false
+    }
+}
+
+pub fn role_rank(
+    role: &str
+) -> u8 {
+    match role {
+        ROLE_ADMIN => 2,
+        ROLE_MODERATOR => 1,
+        _ => 0
+    }
+}
+
+pub fn outranks(
+    actor: &User,
+    target: &User
+) -> bool {
+    role_rank(&actor.user_role) > role_rank(&target.user_role)
+}
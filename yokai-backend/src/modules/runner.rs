@@ -14,38 +14,111 @@ use super::units::AppData;
 use actix_web::HttpServer;
 use super::db::create_user;
 use super::db::user_exists;
+use super::events::EventHub;
+use super::jobs::spawn_jobs;
+use super::roles::ROLE_ADMIN;
 use super::db::get_host_info;
 use sqlx::postgres::Postgres;
 use super::config::get_config;
 use super::api::login_service;
+use super::api::typing_service;
+use super::api::typers_service;
+use super::api::events_service;
 use super::api::logout_service;
 use super::db::create_host_info;
 use super::api::edit_bio_service;
 use super::api::edit_pfp_service;
+use super::api::mark_read_service;
 use super::api::post_file_service;
 use super::api::kick_user_service;
 use actix_web::middleware::Logger;
+use actix_web::web::PayloadConfig;
+use super::api::file_link_service;
+use super::api::chat_pins_service;
+use super::api::open_chat_service;
+use super::api::user_role_service;
+use super::api::audit_log_service;
+use super::api::chat_timer_service;
+use super::api::user_stats_service;
+use super::api::block_user_service;
+use super::api::chat_muted_service;
+use super::presence::PresenceStore;
+use super::api::user_chats_service;
+use super::api::group_role_service;
 use super::models::HostInformation;
 use super::api::serve_file_service;
+use super::api::report_user_service;
+use super::api::accept_chat_service;
+use super::api::user_blocks_service;
+use super::api::chat_pinned_service;
+use super::api::pin_message_service;
+use super::api::group_leave_service;
 use super::api::delete_file_service;
 use super::api::create_chat_service;
 use super::utils::create_connection;
 use super::api::user_create_service;
+use super::api::audit_export_service;
+use super::api::suspend_user_service;
+use super::api::decline_chat_service;
+use super::api::unblock_user_service;
+use super::api::star_message_service;
+use super::api::add_reaction_service;
+use super::api::edit_message_service;
+use super::api::create_group_service;
+use super::api::group_invite_service;
+use super::api::group_remove_service;
+use super::api::serve_avatar_service;
+use super::units::UPLOAD_CHUNK_LIMIT;
+use super::api::upload_chunk_service;
 use super::api::send_message_service;
+use super::api::instance_info_service;
+use super::api::admin_reports_service;
+use super::api::edit_approval_service;
+use super::api::chat_requests_service;
+use super::api::chat_archived_service;
+use super::api::chat_nickname_service;
+use super::api::unpin_message_service;
+use super::api::user_presence_service;
+use super::api::chat_messages_service;
+use super::api::group_members_service;
+use super::api::download_file_service;
+use super::api::upload_create_service;
+use super::api::upload_status_service;
+use super::api::upload_finish_service;
+use super::api::upload_cancel_service;
 use super::api::edit_password_service;
 use super::api::user_contacts_service;
 use super::api::invite_create_service;
+use super::api::user_directory_service;
+use super::api::instance_stats_service;
+use super::api::unsuspend_user_service;
+use super::api::report_message_service;
+use super::api::resolve_report_service;
+use super::api::unstar_message_service;
+use super::api::edit_last_seen_service;
+use super::api::message_thread_service;
+use super::api::delete_message_service;
+use super::api::storage_report_service;
 use super::api::delete_account_service;
+use super::api::instance_asset_service;
+use super::api::remove_reaction_service;
+use super::api::message_history_service;
 use super::api::decrypt_message_service;
 use super::api::list_user_files_service;
+use super::api::starred_messages_service;
 use super::api::list_user_tokens_service;
+use super::api::contacts_presence_service;
 use super::api::edit_user_primary_service;
 use super::api::edit_display_name_service;
-use super::api::edit_host_primary_service;
-use super::api::edit_host_tertiary_service;
+use super::units::MULTIPART_METADATA_LIMIT;
+use super::api::unschedule_message_service;
+use super::api::scheduled_messages_service;
+use super::api::presence_heartbeat_service;
+use super::api::edit_read_receipts_service;
+use super::api::edit_host_branding_service;
 use super::api::edit_user_tertiary_service;
 use super::api::edit_user_secondary_service;
-use super::api::edit_host_secondary_service;
+use actix_multipart::form::MultipartFormConfig;
 
 pub async fn run_app() -> Result<(), YokaiErr>{
     let config_vars: Config = match get_config(){
@@ -88,7 +161,7 @@ pub async fn run_app() -> Result<(), YokaiErr>{
         let _a_info: User = match create_user(
             &config_vars.admin_username,
             &config_vars.admin_password,
-            &true,
+            ROLE_ADMIN,
             &config_vars.admin_description,
             &config_vars.admin_display_name,
             &config_vars.admin_primary_color,
@@ -104,36 +177,104 @@ pub async fn run_app() -> Result<(), YokaiErr>{
         }; 
     }
     let app_data: AppData = AppData{
-        pool: db_connection
+        pool: db_connection,
+        max_upload_size: config_vars.max_upload_size,
+        signing_key: config_vars.signing_key.clone(),
+        clamav_socket: config_vars.clamav_socket.clone(),
+        edit_window: config_vars.edit_window,
+        trusted_proxies: config_vars.trusted_proxies.clone(),
+        events: EventHub::new(),
+        presence: PresenceStore::new()
     };
     let state_data: Data<AppData> = Data::new(app_data);
+    spawn_jobs(state_data.clone());
     let server_addr: String = format!("{}:{}", &config_vars.app_host, &config_vars.app_port);
+    let multipart_limit: usize = config_vars.max_upload_size as usize + MULTIPART_METADATA_LIMIT;
     let server = match HttpServer::new(
         move || {
             let cors = Cors::default()
                 .allowed_origin("*")
-                .allowed_methods(vec!["GET", "POST"]);
+                .allowed_methods(vec!["GET", "POST", "PATCH"]);
             App::new()
                 .wrap(cors)
                 .wrap(Logger::new("%a %{User-Agent}i"))
                 .app_data(state_data.clone())
+                .app_data(PayloadConfig::new(UPLOAD_CHUNK_LIMIT))
+                .app_data(MultipartFormConfig::default().total_limit(multipart_limit))
                 .service(kick_user_service)
+                .service(user_role_service)
+                .service(audit_log_service)
+                .service(audit_export_service)
+                .service(user_directory_service)
+                .service(user_stats_service)
+                .service(instance_stats_service)
+                .service(instance_info_service)
+                .service(instance_asset_service)
+                .service(suspend_user_service)
+                .service(unsuspend_user_service)
+                .service(report_user_service)
+                .service(report_message_service)
+                .service(admin_reports_service)
+                .service(resolve_report_service)
                 .service(create_chat_service)
+                .service(open_chat_service)
+                .service(chat_requests_service)
+                .service(accept_chat_service)
+                .service(decline_chat_service)
                 .service(login_service)
                 .service(user_create_service)
                 .service(user_contacts_service)
                 .service(send_message_service)
+                .service(presence_heartbeat_service)
+                .service(contacts_presence_service)
+                .service(user_presence_service)
+                .service(typing_service)
+                .service(typers_service)
+                .service(chat_timer_service)
+                .service(scheduled_messages_service)
+                .service(unschedule_message_service)
+                .service(pin_message_service)
+                .service(unpin_message_service)
+                .service(chat_pins_service)
+                .service(star_message_service)
+                .service(unstar_message_service)
+                .service(starred_messages_service)
+                .service(chat_archived_service)
+                .service(chat_muted_service)
+                .service(chat_pinned_service)
+                .service(chat_nickname_service)
+                .service(block_user_service)
+                .service(unblock_user_service)
+                .service(user_blocks_service)
+                .service(edit_last_seen_service)
+                .service(mark_read_service)
+                .service(edit_read_receipts_service)
+                .service(edit_approval_service)
+                .service(add_reaction_service)
+                .service(remove_reaction_service)
+                .service(events_service)
+                .service(message_thread_service)
+                .service(edit_message_service)
+                .service(delete_message_service)
+                .service(message_history_service)
+                .service(user_chats_service)
+                .service(chat_messages_service)
+                .service(create_group_service)
+                .service(group_members_service)
+                .service(group_invite_service)
+                .service(group_remove_service)
+                .service(group_leave_service)
+                .service(group_role_service)
                 .service(list_user_files_service)
                 .service(logout_service)
                 .service(invite_create_service)
                 .service(list_user_tokens_service)
                 .service(serve_file_service)
+                .service(serve_avatar_service)
                 .service(post_file_service)
                 .service(edit_user_tertiary_service)
-                .service(edit_host_tertiary_service)
+                .service(edit_host_branding_service)
                 .service(edit_password_service)
-                .service(edit_host_secondary_service)
-                .service(edit_host_primary_service)
                 .service(edit_pfp_service)
                 .service(delete_file_service)
                 .service(edit_display_name_service)
@@ -142,6 +283,14 @@ pub async fn run_app() -> Result<(), YokaiErr>{
                 .service(edit_user_primary_service)
                 .service(decrypt_message_service)
                 .service(delete_account_service)
+                .service(upload_create_service)
+                .service(upload_chunk_service)
+                .service(upload_status_service)
+                .service(upload_finish_service)
+                .service(upload_cancel_service)
+                .service(file_link_service)
+                .service(download_file_service)
+                .service(storage_report_service)
         }
     ).bind(server_addr){
         Ok(server) => server,
@@ -0,0 +1,219 @@
+/*
+Yokai by Alyx Shang.
+Licensed under the FSL v1.
+*/
+
+use sqlx::Pool;
+use std::fs::File;
+use std::io::Read;
+use std::io::Write;
+use std::fs::rename;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use super::err::YokaiErr;
+use actix_web::web::block;
+use super::models::UserFile;
+use sqlx::postgres::Postgres;
+use super::db::set_file_scan;
+use super::db::get_file_by_id;
+use super::utils::storage_dir;
+use std::os::unix::net::UnixStream;
+
+pub const CLEAN_VERDICT: &str = "clean";
+
+pub const INFECTED_VERDICT: &str = "infected";
+
+pub const SCAN_TIMEOUT: u64 = 120;
+
+pub const SCAN_CHUNK_SIZE: usize = 65536;
+
+pub fn scan_file(
+    socket: &str,
+    path: &Path
+) -> Result<String, YokaiErr> {
+    let mut file: File = match File::open(path){
+        Ok(file) => file,
+        Err(e) => return Err::<String, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut stream: UnixStream = match UnixStream::connect(socket){
+        Ok(stream) => stream,
+        Err(e) => return Err::<String, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let timeout: Option<Duration> = Some(Duration::from_secs(SCAN_TIMEOUT));
+    let _: () = match stream.set_read_timeout(timeout){
+        Ok(_f) => {},
+        Err(e) => return Err::<String, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match stream.set_write_timeout(timeout){
+        Ok(_f) => {},
+        Err(e) => return Err::<String, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match stream.write_all(b"zINSTREAM\0"){
+        Ok(_f) => {},
+        Err(e) => return Err::<String, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut buffer: Vec<u8> = vec![0;SCAN_CHUNK_SIZE];
+    loop {
+        let read: usize = match file.read(&mut buffer){
+            Ok(read) => read,
+            Err(e) => return Err::<String, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        let _: () = match stream.write_all(&(read as u32).to_be_bytes()){
+            Ok(_f) => {},
+            Err(e) => return Err::<String, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        if read == 0 {
+            break;
+        }
+        let _: () = match stream.write_all(&buffer[..read]){
+            Ok(_f) => {},
+            Err(e) => return Err::<String, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+    }
+    let mut reply: Vec<u8> = Vec::new();
+    let mut byte: [u8;1] = [0;1];
+    loop {
+        let read: usize = match stream.read(&mut byte){
+            Ok(read) => read,
+            Err(e) => return Err::<String, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        if read == 0 || byte[0] == 0 {
+            break;
+        }
+        reply.push(byte[0]);
+    }
+    let reply_str: String = String::from_utf8_lossy(&reply)
+        .trim()
+        .to_string();
+    Ok(reply_str)
+}
+
+pub fn parse_verdict(
+    reply: &str
+) -> String {
+    let result: &str = match reply.split_once(": "){
+        Some((_stream, result)) => result,
+        None => reply
+    };
+    if result == "OK" {
+        CLEAN_VERDICT.to_string()
+    }
+    else if let Some(signature) = result.strip_suffix(" FOUND") {
+        format!("{}: {}", INFECTED_VERDICT, signature)
+    }
+    else {
+        format!("error: {}", result)
+    }
+}
+
+pub fn is_quarantined(
+    file: &UserFile
+) -> bool {
+    match &file.scan_verdict {
+        Some(verdict) => verdict != CLEAN_VERDICT,
+        None => false
+    }
+}
+
+pub async fn scan_path(
+    path: &Path,
+    socket: &Option<String>
+) -> Result<(), YokaiErr> {
+    let socket_path: String = match socket {
+        Some(socket_path) => socket_path.clone(),
+        None => return Ok(())
+    };
+    let scan_path: PathBuf = path.to_path_buf();
+    let verdict: String = match block(
+        move || scan_file(&socket_path, scan_path.as_path())
+    ).await {
+        Ok(Ok(reply)) => parse_verdict(&reply),
+        Ok(Err(e)) => format!("error: {}", &e.to_string()),
+        Err(e) => format!("error: {}", &e.to_string())
+    };
+    if verdict == CLEAN_VERDICT {
+        Ok(())
+    }
+    else {
+        Err::<(), YokaiErr>(
+            YokaiErr::new(&format!("File failed the malware scan ({}).", verdict))
+        )
+    }
+}
+
+pub async fn scan_user_file(
+    file_id: &str,
+    socket: &Option<String>,
+    pool: &Pool<Postgres>
+) -> Result<bool, YokaiErr> {
+    let socket_path: String = match socket {
+        Some(socket_path) => socket_path.clone(),
+        None => return Ok(true)
+    };
+    let file: UserFile = match get_file_by_id(
+        file_id,
+        pool
+    ).await {
+        Ok(file) => file,
+        Err(e) => return Err::<bool, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let scan_path: PathBuf = PathBuf::from(&file.file_path);
+    let verdict: String = match block(
+        move || scan_file(&socket_path, scan_path.as_path())
+    ).await {
+        Ok(Ok(reply)) => parse_verdict(&reply),
+        Ok(Err(e)) => format!("error: {}", &e.to_string()),
+        Err(e) => format!("error: {}", &e.to_string())
+    };
+    let mut file_path: String = file.file_path.clone();
+    let quarantined: bool = verdict != CLEAN_VERDICT;
+    if quarantined {
+        let mut quarantine: PathBuf = match storage_dir("user_files/quarantine"){
+            Ok(quarantine) => quarantine,
+            Err(e) => return Err::<bool, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        quarantine.push(&file.file_id);
+        let _: () = match rename(&file.file_path, quarantine.as_path()){
+            Ok(_f) => {},
+            Err(e) => return Err::<bool, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        file_path = quarantine.display().to_string();
+    }
+    let _: () = match set_file_scan(
+        &file.file_id,
+        &verdict,
+        &file_path,
+        pool
+    ).await {
+        Ok(_f) => {},
+        Err(e) => return Err::<bool, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(!quarantined)
+}
@@ -4,58 +4,1158 @@ Licensed under the FSL v1.
 */
 
 use sqlx::Pool;
+use std::io::Read;
 use std::env::var;
+use std::io::Write;
+use sqlx::Transaction;
+use std::path::PathBuf;
+use image::ImageFormat;
+use super::models::User;
+use super::models::Chat;
+use image::DynamicImage;
+use std::time::Duration;
+use super::db::open_chat;
+use super::db::mark_read;
 use super::err::YokaiErr;
+use super::models::Report;
+use super::db::block_user;
 use super::units::KeyPair;
 use super::utils::rfc2282;
+use std::time::SystemTime;
+use super::db::get_reports;
+use actix_web::HttpRequest;
+use super::models::Message;
+use super::db::create_user;
+use super::events::EventHub;
+use super::db::add_reaction;
+use super::db::record_audit;
+use super::db::star_message;
+use super::db::create_group;
+use super::db::suspend_user;
+use super::roles::ROLE_USER;
+use super::models::UserFile;
+use super::db::purge_account;
+use super::db::count_reports;
+use super::db::create_report;
+use super::db::unmuted_users;
+use super::utils::hash_bytes;
+use super::utils::request_ip;
+use super::roles::Permission;
+use super::roles::ROLE_ADMIN;
+use super::db::set_user_role;
 use sqlx::postgres::Postgres;
+use super::units::OWNER_ROLE;
+use super::db::resolve_report;
+use super::db::set_chat_muted;
+use super::models::MessageKey;
+use super::db::get_audit_page;
+use super::models::AuditEntry;
+use super::db::get_user_stars;
+use super::units::MEMBER_ROLE;
+use super::models::ChatMember;
+use super::db::is_chat_member;
+use super::db::get_user_by_id;
+use super::db::create_message;
+use super::db::get_chat_by_id;
+use super::db::delete_account;
+use super::utils::check_emoji;
+use super::scanner::scan_file;
+use super::scanner::scan_path;
 use super::utils::hash_string;
+use super::images::load_image;
+use super::images::avatar_url;
+use super::jobs::find_orphans;
+use std::collections::HashSet;
+use super::db::get_chat_member;
+use super::db::get_message_key;
+use super::utils::like_pattern;
+use super::db::add_chat_member;
+use super::utils::is_suspended;
+use super::units::MAX_PAGE_SIZE;
+use super::utils::rfc2822_after;
+use super::roles::is_valid_role;
 use super::utils::check_message;
+use super::utils::sign_download;
+use super::db::get_chat_requests;
+use super::db::attachment_in_use;
+use super::units::ACTION_DISMISS;
+use super::db::set_chat_nickname;
+use actix_web::test::TestRequest;
+use super::roles::has_permission;
+use super::roles::ROLE_MODERATOR;
+use super::db::awaiting_approval;
+use super::db::get_chat_messages;
+use super::db::get_message_by_id;
+use super::presence::AWAY_STATUS;
 use super::utils::check_username;
 use super::utils::check_password;
+use super::units::AUDIT_HOST_EDIT;
+use super::db::remove_chat_member;
+use super::db::is_blocked_between;
+use super::images::strip_metadata;
+use super::db::edit_user_approval;
+use super::scanner::parse_verdict;
+use super::scanner::CLEAN_VERDICT;
 use super::utils::check_color_str;
 use super::utils::decrypt_message;
 use super::utils::encrypt_message;
+use super::units::RenderedVariant;
+use super::utils::generate_secret;
+use super::utils::verify_download;
+use super::units::MAX_OPEN_UPLOADS;
+use super::db::get_thread_messages;
+use super::scanner::is_quarantined;
+use super::images::render_branding;
+use super::db::is_chat_participant;
+use super::db::active_participants;
+use super::db::accept_chat_request;
+use super::presence::PresenceStore;
 use super::utils::generate_keypair;
+use super::images::render_variants;
+use super::db::decline_chat_request;
+use super::units::MAX_MESSAGE_BYTES;
+use super::models::ScheduledMessage;
+use super::presence::OFFLINE_STATUS;
 use super::utils::create_connection;
-
-use super::db::create_user;
+use super::db::create_upload_session;
+use super::db::delete_upload_session;
+use super::db::get_scheduled_message;
+use super::utils::edit_optional_text;
+use std::os::unix::net::UnixListener;
+use super::jobs::ORPHAN_GRACE_PERIOD;
+use super::units::MAX_NICKNAME_LENGTH;
+use super::jobs::reap_expired_messages;
+use super::db::create_scheduled_message;
+use super::db::cancel_scheduled_message;
+use super::utils::check_contact_address;
+use tokio::sync::mpsc::UnboundedReceiver;
+use super::jobs::deliver_scheduled_messages;
 
 #[tokio::test]
 pub async fn test_utils(){
     let rfc_str: String = rfc2282();
-    assert_eq!(rfc_str.is_empty(), false);
+    assert!(!rfc_str.is_empty());
     let hashed_str: String = hash_string("Hello World!");
-    assert_eq!(hashed_str.is_empty(), false);
+    assert!(!hashed_str.is_empty());
     let username_chk_t: bool = check_username("alyxshang");
-    assert_eq!(username_chk_t, true);
+    assert!(username_chk_t);
     let username_chk_f: bool = check_username("alyxshangHH");
-    assert_eq!(username_chk_f, false);
+    assert!(!username_chk_f);
     let msg_chk: bool = check_message("Hi my name is Alyx.");
-    assert_eq!(msg_chk, true);
+    assert!(msg_chk);
+    assert!(check_message(&"a".repeat(MAX_MESSAGE_BYTES)));
+    assert!(!check_message(&"é".repeat(MAX_MESSAGE_BYTES / 2 + 1)));
     let pwd_chk: bool = check_password("WrongCodeIsEvil");
-    assert_eq!(pwd_chk, true);
+    assert!(pwd_chk);
     let color_chk_t: bool = check_color_str("#DF0045");
-    assert_eq!(color_chk_t, true);
+    assert!(color_chk_t);
     let color_chk_f: bool = check_color_str("#DF00450");
-    assert_eq!(color_chk_f, false);
+    assert!(!color_chk_f);
+    assert_eq!(like_pattern("50%_off"), "%50\\%\\_off%");
+    let proxies: Vec<String> = vec!["10.0.0.1".to_string(), "10.0.0.2".to_string()];
+    let proxied: HttpRequest = TestRequest::default()
+        .peer_addr("10.0.0.1:4000".parse().expect("Error parsing address."))
+        .insert_header(("X-Forwarded-For", "198.51.100.9, 203.0.113.7, 10.0.0.2"))
+        .to_http_request();
+    assert_eq!(request_ip(&proxied, &proxies), Some("203.0.113.7".to_string()));
+    let direct: HttpRequest = TestRequest::default()
+        .peer_addr("192.0.2.4:4000".parse().expect("Error parsing address."))
+        .insert_header(("X-Forwarded-For", "203.0.113.7"))
+        .to_http_request();
+    assert_eq!(request_ip(&direct, &proxies), Some("192.0.2.4".to_string()));
+    assert!(check_emoji("👍"));
+    assert!(!check_emoji("ok"));
+    assert!(!check_emoji(""));
+    assert!(check_emoji("👍🏽"));
+    assert!(check_emoji("❤️"));
+    assert!(check_emoji("👩‍💻"));
+    assert!(check_emoji("🇯🇵"));
+    assert!(check_emoji("1️⃣"));
+    assert!(!check_emoji("é"));
+    assert!(!check_emoji("日本"));
+    assert!(!check_emoji("👍a"));
+    assert!(!check_emoji("1"));
+    assert!(check_contact_address("admin@yokai.example"));
+    assert!(!check_contact_address("admin@localhost"));
+    assert!(!check_contact_address("not an address"));
+    let kept: Option<String> = edit_optional_text(&Some("Yokai".to_string()), &None, 8)
+        .expect("Error editing text.");
+    assert_eq!(kept, Some("Yokai".to_string()));
+    let cleared: Option<String> = edit_optional_text(&kept, &Some(" ".to_string()), 8)
+        .expect("Error editing text.");
+    assert!(cleared.is_none());
+    assert!(edit_optional_text(&None, &Some("Too long!".to_string()), 8).is_err());
+    assert!(is_valid_role("moderator"));
+    assert!(!is_valid_role("root"));
     let keys: KeyPair = generate_keypair()
         .expect("Error making keys.");
-    assert_eq!(keys.public_key.is_empty(), false);
-    assert_eq!(keys.private_key.is_empty(), false);
+    assert!(!keys.public_key.is_empty());
+    assert!(!keys.private_key.is_empty());
     let db_url: String = var("YOKAI_DB_URL")
         .expect("Yokai DB URL not found.");
     let conn: Result<Pool<Postgres>, YokaiErr> = create_connection(&db_url)
         .await;
-    assert_eq!(conn.is_ok(), true);
+    assert!(conn.is_ok());
     let msg: String = "Hello World!".to_string();
     let encrypted: String = encrypt_message(&msg, &keys.public_key)
         .expect("Error encrypting.");
     let decrypted: String = decrypt_message(&encrypted, &keys.private_key)
         .expect("Error decrypting.");
     assert_eq!(msg, decrypted);
+    let secret: String = generate_secret()
+        .expect("Error generating secret.");
+    let expires: i64 = chrono::Utc::now().timestamp() + 60;
+    let signature: String = sign_download("file", &expires, &secret)
+        .expect("Error signing download.");
+    assert!(verify_download("file", &expires, &signature, &secret));
+    assert!(!verify_download("other", &expires, &signature, &secret));
+    assert!(!verify_download("file", &(expires - 120), &signature, &secret));
+}
+
+#[tokio::test]
+pub async fn test_images(){
+    let mut dir: PathBuf = std::env::temp_dir();
+    dir.push("yokai_test_images");
+    std::fs::create_dir_all(&dir)
+        .expect("Error creating directory.");
+    let mut source: PathBuf = dir.clone();
+    source.push("source.png");
+    DynamicImage::new_rgb8(640, 480)
+        .save_with_format(&source, ImageFormat::Png)
+        .expect("Error saving image.");
+    let rendered: Vec<RenderedVariant> = render_variants("test", &source, &dir)
+        .expect("Error rendering variants.");
+    assert_eq!(rendered.len(), 2);
+    for variant in rendered {
+        let img: DynamicImage = load_image(&PathBuf::from(&variant.file_path))
+            .expect("Error loading variant.");
+        assert_eq!(img.width(), img.height());
+    }
+    let mut text: PathBuf = dir.clone();
+    text.push("text.png");
+    std::fs::write(&text, "Hello World!")
+        .expect("Error writing file.");
+    assert!(render_variants("text", &text, &dir).is_err());
+    let mut branding: PathBuf = dir.clone();
+    branding.push("branding.png");
+    render_branding(&source, &branding)
+        .expect("Error rendering branding.");
+    assert!(load_image(&branding).is_ok());
+    assert!(render_branding(&text, &branding).is_err());
+    let mut photo: PathBuf = dir.clone();
+    photo.push("photo");
+    DynamicImage::new_rgb8(64, 48)
+        .save_with_format(&photo, ImageFormat::Jpeg)
+        .expect("Error saving image.");
+    let mut jpeg: Vec<u8> = std::fs::read(&photo)
+        .expect("Error reading image.");
+    let exif: Vec<u8> = [
+        &[0xFF, 0xE1, 0x00, 0x10][..],
+        b"Exif\0\0GPSDATA\0\0\0"
+    ].concat();
+    jpeg.splice(2..2, exif);
+    std::fs::write(&photo, &jpeg)
+        .expect("Error writing image.");
+    strip_metadata(&photo)
+        .expect("Error stripping metadata.");
+    let stripped: Vec<u8> = std::fs::read(&photo)
+        .expect("Error reading image.");
+    assert!(!stripped.windows(4).any(|window| window == b"Exif"));
+    assert!(load_image(&photo).is_ok());
+    let mut animation: PathBuf = dir.clone();
+    animation.push("animation");
+    DynamicImage::new_rgba8(16, 16)
+        .save_with_format(&animation, ImageFormat::Gif)
+        .expect("Error saving image.");
+    let mut gif: Vec<u8> = std::fs::read(&animation)
+        .expect("Error reading image.");
+    let comment: Vec<u8> = [
+        &[0x21, 0xFE, 0x07][..],
+        b"GPSDATA\0"
+    ].concat();
+    let trailer: usize = gif.len() - 1;
+    gif.splice(trailer..trailer, comment);
+    std::fs::write(&animation, &gif)
+        .expect("Error writing image.");
+    strip_metadata(&animation)
+        .expect("Error stripping metadata.");
+    let stripped: Vec<u8> = std::fs::read(&animation)
+        .expect("Error reading image.");
+    assert!(!stripped.windows(7).any(|window| window == b"GPSDATA"));
+    assert!(load_image(&animation).is_ok());
+    let mut notes: PathBuf = dir.clone();
+    notes.push("notes");
+    std::fs::write(&notes, "Hello World!")
+        .expect("Error writing file.");
+    strip_metadata(&notes)
+        .expect("Error skipping non-image file.");
+    assert_eq!(avatar_url("test", "avatar"), "/avatar/test/avatar");
+}
+
+#[tokio::test]
+pub async fn test_jobs(){
+    let mut dir: PathBuf = std::env::temp_dir();
+    dir.push("yokai_test_jobs");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)
+        .expect("Error creating directory.");
+    let old: SystemTime = SystemTime::now() - Duration::from_secs(ORPHAN_GRACE_PERIOD + 60);
+    let mut known: HashSet<String> = HashSet::new();
+    for name in ["known", "orphan", "fresh"] {
+        let mut path: PathBuf = dir.clone();
+        path.push(name);
+        let file: std::fs::File = std::fs::File::create(&path)
+            .expect("Error creating file.");
+        if name != "fresh" {
+            file.set_modified(old)
+                .expect("Error setting modification time.");
+        }
+        if name == "known" {
+            known.insert(path.display().to_string());
+        }
+    }
+    let orphans: Vec<PathBuf> = find_orphans(&dir, &known)
+        .expect("Error finding orphans.");
+    assert_eq!(orphans.len(), 1);
+    assert!(orphans[0].ends_with("orphan"));
+}
+
+#[tokio::test]
+pub async fn test_scanner(){
+    let mut dir: PathBuf = std::env::temp_dir();
+    dir.push("yokai_test_scanner");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)
+        .expect("Error creating directory.");
+    let mut socket: PathBuf = dir.clone();
+    socket.push("clamd.sock");
+    let listener: UnixListener = UnixListener::bind(&socket)
+        .expect("Error binding socket.");
+    std::thread::spawn(
+        move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.expect("Error accepting stream.");
+                let mut command: [u8;10] = [0;10];
+                stream.read_exact(&mut command)
+                    .expect("Error reading command.");
+                let mut body: Vec<u8> = Vec::new();
+                loop {
+                    let mut size: [u8;4] = [0;4];
+                    stream.read_exact(&mut size)
+                        .expect("Error reading chunk size.");
+                    let size: usize = u32::from_be_bytes(size) as usize;
+                    if size == 0 {
+                        break;
+                    }
+                    let mut chunk: Vec<u8> = vec![0;size];
+                    stream.read_exact(&mut chunk)
+                        .expect("Error reading chunk.");
+                    body.extend(chunk);
+                }
+                let reply: &[u8] = if body.starts_with(b"EICAR") {
+                    b"stream: Eicar-Test-Signature FOUND\0"
+                }
+                else {
+                    b"stream: OK\0"
+                };
+                stream.write_all(reply)
+                    .expect("Error writing reply.");
+            }
+        }
+    );
+    let socket_path: String = socket.display().to_string();
+    let mut clean: PathBuf = dir.clone();
+    clean.push("clean");
+    std::fs::write(&clean, "Hello World!")
+        .expect("Error writing file.");
+    let mut infected: PathBuf = dir.clone();
+    infected.push("infected");
+    std::fs::write(&infected, "EICAR")
+        .expect("Error writing file.");
+    let clean_reply: String = scan_file(&socket_path, &clean)
+        .expect("Error scanning file.");
+    assert_eq!(parse_verdict(&clean_reply), CLEAN_VERDICT);
+    let infected_reply: String = scan_file(&socket_path, &infected)
+        .expect("Error scanning file.");
+    assert_eq!(
+        parse_verdict(&infected_reply),
+        "infected: Eicar-Test-Signature"
+    );
+    assert!(parse_verdict("stream: Size limit exceeded ERROR").starts_with("error"));
+    let mut file: UserFile = UserFile{
+        file_id: "scanned".to_string(),
+        file_path: clean.display().to_string(),
+        file_owner: "alyxshang".to_string(),
+        missing: false,
+        scan_verdict: Some(CLEAN_VERDICT.to_string()),
+        file_size: 0
+    };
+    assert!(!is_quarantined(&file));
+    file.scan_verdict = Some(parse_verdict("stream: Size limit exceeded ERROR"));
+    assert!(is_quarantined(&file));
+    file.scan_verdict = Some(parse_verdict(&infected_reply));
+    assert!(is_quarantined(&file));
+    assert!(scan_path(&clean, &Some(socket_path.clone())).await.is_ok());
+    assert!(scan_path(&infected, &Some(socket_path.clone())).await.is_err());
+    let mut missing_socket: PathBuf = dir.clone();
+    missing_socket.push("missing.sock");
+    assert!(
+        scan_path(&clean, &Some(missing_socket.display().to_string()))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+pub async fn test_presence(){
+    let store: PresenceStore = PresenceStore::new();
+    let (status, last_seen) = store.status("alyxshang")
+        .expect("Error reading presence.");
+    assert_eq!(status, OFFLINE_STATUS);
+    assert!(last_seen.is_none());
+    store.touch("alyxshang", AWAY_STATUS)
+        .expect("Error updating presence.");
+    let (status, last_seen) = store.status("alyxshang")
+        .expect("Error reading presence.");
+    assert_eq!(status, AWAY_STATUS);
+    assert!(last_seen.is_some());
+    assert!(store.touch("alyxshang", "busy").is_err());
+    store.set_typing("chat", "alyxshang", &true)
+        .expect("Error setting typing state.");
+    assert_eq!(store.typers("chat").expect("Error reading typers."), vec!["alyxshang"]);
+    store.set_typing("chat", "alyxshang", &false)
+        .expect("Error setting typing state.");
+    assert!(store.typers("chat").expect("Error reading typers.").is_empty());
+    store.set_typing("chat", "alyxshang", &true)
+        .expect("Error setting typing state.");
+    store.forget("alyxshang")
+        .expect("Error clearing presence.");
+    let (status, last_seen) = store.status("alyxshang")
+        .expect("Error reading presence.");
+    assert_eq!(status, OFFLINE_STATUS);
+    assert!(last_seen.is_none());
+    assert!(store.typers("chat").expect("Error reading typers.").is_empty());
+    let hub: EventHub = EventHub::new();
+    let mut receiver: UnboundedReceiver<String> = hub.subscribe("alyxshang")
+        .expect("Error subscribing.");
+    hub.disconnect("alyxshang")
+        .expect("Error disconnecting.");
+    assert!(receiver.recv().await.is_none());
+    let dropped: UnboundedReceiver<String> = hub.subscribe("idle")
+        .expect("Error subscribing.");
+    drop(dropped);
+    assert_eq!(hub.subscriber_count("idle").expect("Error counting subscribers."), 1);
+    let _live: UnboundedReceiver<String> = hub.subscribe("alyxshang")
+        .expect("Error subscribing.");
+    assert_eq!(hub.subscriber_count("idle").expect("Error counting subscribers."), 0);
+    assert_eq!(hub.subscriber_count("alyxshang").expect("Error counting subscribers."), 1);
 }
 
 #[tokio::test]
 pub async fn test_database(){
 }
+
+async fn test_pool() -> Pool<Postgres> {
+    let db_url: String = var("YOKAI_DB_URL")
+        .expect("Yokai DB URL not found.");
+    create_connection(&db_url)
+        .await
+        .expect("Error connecting to database.")
+}
+
+async fn remove_test_user(
+    username: &str,
+    pool: &Pool<Postgres>
+) {
+    let _ = sqlx::query("UPDATE users SET user_role = $1 WHERE username = $2")
+        .bind(ROLE_USER)
+        .bind(username)
+        .execute(pool)
+        .await;
+    let _ = delete_account(username, pool).await;
+}
+
+async fn test_user(
+    username: &str,
+    pool: &Pool<Postgres>
+) -> User {
+    remove_test_user(username, pool).await;
+    create_user(
+        username,
+        "WrongCodeIsEvil",
+        ROLE_USER,
+        "Test user.",
+        username,
+        "#DF0045",
+        "#DF0045",
+        "#DF0045",
+        &None,
+        pool
+    )
+        .await
+        .expect("Error creating user.")
+}
+
+#[tokio::test]
+pub async fn test_scheduled_messages(){
+    let pool: Pool<Postgres> = test_pool().await;
+    let sender: User = test_user("schedsender", &pool).await;
+    let receiver: User = test_user("schedreceiver", &pool).await;
+    let chat: Chat = open_chat(&sender.username, &receiver.username, &pool)
+        .await
+        .expect("Error opening chat.");
+    let events: EventHub = EventHub::new();
+    let mut inbox: UnboundedReceiver<String> = events.subscribe(&receiver.username)
+        .expect("Error subscribing to events.");
+    assert!(
+        create_scheduled_message("Hi!", &sender.username, &chat.chat_id, &None, &None, &rfc2822_after(&-60), &pool)
+            .await
+            .is_err()
+    );
+    assert!(
+        create_scheduled_message("Hi!", &sender.username, &chat.chat_id, &Some("nofile".to_string()), &None, &rfc2822_after(&60), &pool)
+            .await
+            .is_err()
+    );
+    assert!(
+        create_scheduled_message("Hi!", &sender.username, &chat.chat_id, &None, &Some("nomessage".to_string()), &rfc2822_after(&60), &pool)
+            .await
+            .is_err()
+    );
+    let cancelled: ScheduledMessage = create_scheduled_message("Hi!", &sender.username, &chat.chat_id, &None, &None, &rfc2822_after(&60), &pool)
+        .await
+        .expect("Error scheduling message.");
+    assert!(cancel_scheduled_message(&cancelled.schedule_id, &receiver.username, &pool).await.is_err());
+    cancel_scheduled_message(&cancelled.schedule_id, &sender.username, &pool)
+        .await
+        .expect("Error cancelling message.");
+    assert!(get_scheduled_message(&cancelled.schedule_id, &pool).await.is_err());
+    let due: ScheduledMessage = create_scheduled_message("Hi!", &sender.username, &chat.chat_id, &None, &None, &rfc2822_after(&60), &pool)
+        .await
+        .expect("Error scheduling message.");
+    sqlx::query("UPDATE scheduled_messages SET send_at = $1 WHERE schedule_id = $2")
+        .bind(rfc2822_after(&-1))
+        .bind(&due.schedule_id)
+        .execute(&pool)
+        .await
+        .expect("Error updating message.");
+    deliver_scheduled_messages(&events, &pool)
+        .await
+        .expect("Error delivering messages.");
+    assert!(get_scheduled_message(&due.schedule_id, &pool).await.is_err());
+    let delivered: Vec<Message> = get_chat_messages(&chat.chat_id, &pool)
+        .await
+        .expect("Error fetching messages.");
+    assert_eq!(delivered.len(), 1);
+    assert!(inbox.try_recv().expect("Missing message event.").starts_with("event: message"));
+    let blocked: ScheduledMessage = create_scheduled_message("Hi!", &sender.username, &chat.chat_id, &None, &None, &rfc2822_after(&60), &pool)
+        .await
+        .expect("Error scheduling message.");
+    block_user(&receiver.username, &sender.username, &pool)
+        .await
+        .expect("Error blocking user.");
+    sqlx::query("UPDATE scheduled_messages SET send_at = $1 WHERE schedule_id = $2")
+        .bind(rfc2822_after(&-1))
+        .bind(&blocked.schedule_id)
+        .execute(&pool)
+        .await
+        .expect("Error updating message.");
+    deliver_scheduled_messages(&events, &pool)
+        .await
+        .expect("Error delivering messages.");
+    let retried: ScheduledMessage = get_scheduled_message(&blocked.schedule_id, &pool)
+        .await
+        .expect("Scheduled message was dropped.");
+    assert_eq!(retried.attempts, 1);
+    assert!(inbox.try_recv().is_err());
+    assert!(retried.last_error.is_some());
+    assert!(!retried.failed);
+    delete_account(&sender.username, &pool)
+        .await
+        .expect("Error deleting account.");
+    assert!(get_scheduled_message(&blocked.schedule_id, &pool).await.is_err());
+    delete_account(&receiver.username, &pool)
+        .await
+        .expect("Error deleting account.");
+}
+
+#[tokio::test]
+pub async fn test_expiring_messages(){
+    let pool: Pool<Postgres> = test_pool().await;
+    let sender: User = test_user("expirysender", &pool).await;
+    let receiver: User = test_user("expiryreceiver", &pool).await;
+    let chat: Chat = open_chat(&sender.username, &receiver.username, &pool)
+        .await
+        .expect("Error opening chat.");
+    let root: Message = create_message("Hi!", &sender.username, &chat.chat_id, &None, &None, &pool)
+        .await
+        .expect("Error sending message.");
+    let expired: Message = create_message("Bye!", &sender.username, &chat.chat_id, &None, &Some(root.msg_id.clone()), &pool)
+        .await
+        .expect("Error sending message.");
+    let malformed: Message = create_message("Hm?", &sender.username, &chat.chat_id, &None, &None, &pool)
+        .await
+        .expect("Error sending message.");
+    sqlx::query("UPDATE messages SET expires_at = $1 WHERE msg_id = $2")
+        .bind(rfc2822_after(&-1))
+        .bind(&expired.msg_id)
+        .execute(&pool)
+        .await
+        .expect("Error updating message.");
+    let history: Vec<Message> = get_chat_messages(&chat.chat_id, &pool)
+        .await
+        .expect("Error fetching messages.");
+    assert!(history.iter().all(|message| message.msg_id != expired.msg_id));
+    let thread: Vec<Message> = get_thread_messages(&root.msg_id, &pool)
+        .await
+        .expect("Error fetching thread.");
+    assert_eq!(thread.len(), 1);
+    sqlx::query("UPDATE messages SET expires_at = $1 WHERE msg_id = $2")
+        .bind("2000-01-01 00:00:00+00")
+        .bind(&malformed.msg_id)
+        .execute(&pool)
+        .await
+        .expect("Error updating message.");
+    let removed: usize = reap_expired_messages(&pool)
+        .await
+        .expect("Error reaping messages.");
+    assert!(removed >= 1);
+    assert!(get_message_by_id(&expired.msg_id, &pool).await.is_err());
+    assert!(get_message_by_id(&malformed.msg_id, &pool).await.is_ok());
+    let file_id: String = hash_string("expiryattachment");
+    assert!(!attachment_in_use(&file_id, &pool).await.expect("Error checking attachment."));
+    sqlx::query("UPDATE messages SET attachment = $1 WHERE msg_id = $2")
+        .bind(&file_id)
+        .bind(&root.msg_id)
+        .execute(&pool)
+        .await
+        .expect("Error updating message.");
+    assert!(attachment_in_use(&file_id, &pool).await.expect("Error checking attachment."));
+    remove_test_user(&sender.username, &pool).await;
+    remove_test_user(&receiver.username, &pool).await;
+}
+
+#[tokio::test]
+pub async fn test_message_order(){
+    let pool: Pool<Postgres> = test_pool().await;
+    let sender: User = test_user("ordersender", &pool).await;
+    let receiver: User = test_user("orderreceiver", &pool).await;
+    let chat: Chat = open_chat(&sender.username, &receiver.username, &pool)
+        .await
+        .expect("Error opening chat.");
+    let mut sent: Vec<String> = Vec::new();
+    for offset in [-30, -10, -20] {
+        let message: Message = create_message("Hi!", &sender.username, &chat.chat_id, &None, &None, &pool)
+            .await
+            .expect("Error sending message.");
+        sqlx::query("UPDATE messages SET published = $1 WHERE msg_id = $2")
+            .bind(rfc2822_after(&offset))
+            .bind(&message.msg_id)
+            .execute(&pool)
+            .await
+            .expect("Error updating message.");
+        sent.push(message.msg_id);
+    }
+    let history: Vec<String> = get_chat_messages(&chat.chat_id, &pool)
+        .await
+        .expect("Error fetching messages.")
+        .into_iter()
+        .map(|message| message.msg_id)
+        .collect::<Vec<String>>();
+    assert_eq!(history, vec![sent[0].clone(), sent[2].clone(), sent[1].clone()]);
+    assert!(add_reaction(&sent[0], &receiver.username, "👍", &pool).await.expect("Error reacting."));
+    assert!(!add_reaction(&sent[0], &receiver.username, "👍", &pool).await.expect("Error reacting."));
+    assert!(add_reaction(&sent[0], &receiver.username, "ok", &pool).await.is_err());
+    remove_test_user(&sender.username, &pool).await;
+    remove_test_user(&receiver.username, &pool).await;
+}
+
+#[tokio::test]
+pub async fn test_message_requests(){
+    let pool: Pool<Postgres> = test_pool().await;
+    let sender: User = test_user("requestsender", &pool).await;
+    let receiver: User = test_user("requestreceiver", &pool).await;
+    edit_user_approval(&receiver.username, &true, &pool)
+        .await
+        .expect("Error enabling approval.");
+    let chat: Chat = open_chat(&sender.username, &receiver.username, &pool)
+        .await
+        .expect("Error opening chat.");
+    assert!(chat.pending);
+    assert!(awaiting_approval(&chat, &receiver.username));
+    assert!(is_chat_participant(&chat, &sender.username, &pool).await);
+    assert!(!is_chat_participant(&chat, &receiver.username, &pool).await);
+    let active: Vec<String> = active_participants(&chat, &pool)
+        .await
+        .expect("Error fetching participants.");
+    assert_eq!(active, vec![sender.username.clone()]);
+    create_message("Hi!", &sender.username, &chat.chat_id, &None, &None, &pool)
+        .await
+        .expect("Error sending message.");
+    assert!(
+        create_message("Hi!", &receiver.username, &chat.chat_id, &None, &None, &pool)
+            .await
+            .is_err()
+    );
+    accept_chat_request(&chat.chat_id, &receiver.username, &pool)
+        .await
+        .expect("Error accepting request.");
+    let accepted: Chat = get_chat_by_id(&chat.chat_id, &pool)
+        .await
+        .expect("Error fetching chat.");
+    assert!(is_chat_participant(&accepted, &receiver.username, &pool).await);
+    delete_account(&sender.username, &pool)
+        .await
+        .expect("Error deleting account.");
+    delete_account(&receiver.username, &pool)
+        .await
+        .expect("Error deleting account.");
+}
+
+#[tokio::test]
+pub async fn test_group_invite_requests(){
+    let pool: Pool<Postgres> = test_pool().await;
+    let owner: User = test_user("inviteowner", &pool).await;
+    let stranger: User = test_user("invitestranger", &pool).await;
+    let other: User = test_user("inviteother", &pool).await;
+    edit_user_approval(&stranger.username, &true, &pool)
+        .await
+        .expect("Error enabling approval.");
+    edit_user_approval(&other.username, &true, &pool)
+        .await
+        .expect("Error enabling approval.");
+    let group: Chat = create_group(&owner.username, "Invites", std::slice::from_ref(&stranger.username), &pool)
+        .await
+        .expect("Error creating group.");
+    assert!(!is_chat_member(&group.chat_id, &stranger.username, &pool).await);
+    let requests: Vec<Chat> = get_chat_requests(&stranger.username, &pool)
+        .await
+        .expect("Error fetching requests.");
+    assert_eq!(requests.len(), 1);
+    accept_chat_request(&group.chat_id, &stranger.username, &pool)
+        .await
+        .expect("Error accepting request.");
+    assert!(is_chat_member(&group.chat_id, &stranger.username, &pool).await);
+    add_chat_member(&group.chat_id, &owner.username, &other.username, MEMBER_ROLE, &pool)
+        .await
+        .expect("Error adding member.");
+    assert!(!is_chat_member(&group.chat_id, &other.username, &pool).await);
+    decline_chat_request(&group.chat_id, &other.username, &true, &pool)
+        .await
+        .expect("Error declining request.");
+    assert!(!is_chat_member(&group.chat_id, &other.username, &pool).await);
+    assert!(get_chat_by_id(&group.chat_id, &pool).await.is_ok());
+    assert!(
+        is_blocked_between(&other.username, &owner.username, &pool)
+            .await
+            .expect("Error checking blocks.")
+    );
+    remove_test_user(&owner.username, &pool).await;
+    remove_test_user(&stranger.username, &pool).await;
+    remove_test_user(&other.username, &pool).await;
+}
+
+#[tokio::test]
+pub async fn test_blocking(){
+    let pool: Pool<Postgres> = test_pool().await;
+    let owner: User = test_user("blockowner", &pool).await;
+    let member: User = test_user("blockmember", &pool).await;
+    let outsider: User = test_user("blockoutsider", &pool).await;
+    let group: Chat = create_group(&owner.username, "Blocking", std::slice::from_ref(&member.username), &pool)
+        .await
+        .expect("Error creating group.");
+    create_message("Hi!", &member.username, &group.chat_id, &None, &None, &pool)
+        .await
+        .expect("Error sending message.");
+    block_user(&owner.username, &member.username, &pool)
+        .await
+        .expect("Error blocking user.");
+    assert!(
+        is_blocked_between(&member.username, &owner.username, &pool)
+            .await
+            .expect("Error checking blocks.")
+    );
+    assert!(
+        create_message("Hi!", &member.username, &group.chat_id, &None, &None, &pool)
+            .await
+            .is_err()
+    );
+    block_user(&outsider.username, &owner.username, &pool)
+        .await
+        .expect("Error blocking user.");
+    assert!(
+        add_chat_member(&group.chat_id, &owner.username, &outsider.username, MEMBER_ROLE, &pool)
+            .await
+            .is_err()
+    );
+    assert!(!is_chat_member(&group.chat_id, &outsider.username, &pool).await);
+    assert!(
+        create_group(&owner.username, "Blocked", std::slice::from_ref(&outsider.username), &pool)
+            .await
+            .is_err()
+    );
+    remove_test_user(&owner.username, &pool).await;
+    remove_test_user(&member.username, &pool).await;
+    remove_test_user(&outsider.username, &pool).await;
+}
+
+#[tokio::test]
+pub async fn test_group_departure(){
+    let pool: Pool<Postgres> = test_pool().await;
+    let owner: User = test_user("leaveowner", &pool).await;
+    let member: User = test_user("leavemember", &pool).await;
+    let group: Chat = create_group(&owner.username, "Departure", std::slice::from_ref(&member.username), &pool)
+        .await
+        .expect("Error creating group.");
+    let message: Message = create_message("Hi!", &owner.username, &group.chat_id, &None, &None, &pool)
+        .await
+        .expect("Error sending message.");
+    star_message(&message.msg_id, &member.username, &pool)
+        .await
+        .expect("Error starring message.");
+    assert_eq!(get_user_stars(&member.username, &pool).await.expect("Error fetching stars.").len(), 1);
+    assert!(get_message_key(&message.msg_id, &member.username, &pool).await.is_ok());
+    remove_chat_member(&group.chat_id, &member.username, &pool)
+        .await
+        .expect("Error removing member.");
+    assert!(get_user_stars(&member.username, &pool).await.expect("Error fetching stars.").is_empty());
+    assert!(get_message_key(&message.msg_id, &member.username, &pool).await.is_err());
+    assert!(get_message_key(&message.msg_id, &owner.username, &pool).await.is_ok());
+    sqlx::query("UPDATE messages SET expires_at = $1 WHERE msg_id = $2")
+        .bind(rfc2822_after(&-1))
+        .bind(&message.msg_id)
+        .execute(&pool)
+        .await
+        .expect("Error updating message.");
+    remove_chat_member(&group.chat_id, &owner.username, &pool)
+        .await
+        .expect("Error removing member.");
+    assert!(get_chat_by_id(&group.chat_id, &pool).await.is_err());
+    assert!(get_message_by_id(&message.msg_id, &pool).await.is_err());
+    assert!(get_message_key(&message.msg_id, &owner.username, &pool).await.is_err());
+    remove_test_user(&owner.username, &pool).await;
+    remove_test_user(&member.username, &pool).await;
+}
+
+#[tokio::test]
+pub async fn test_account_purge(){
+    let pool: Pool<Postgres> = test_pool().await;
+    let owner: User = test_user("purgeowner", &pool).await;
+    let member: User = test_user("purgemember", &pool).await;
+    let group: Chat = create_group(&owner.username, "Purge", std::slice::from_ref(&member.username), &pool)
+        .await
+        .expect("Error creating group.");
+    let mut tx: Transaction<'_, Postgres> = pool.begin()
+        .await
+        .expect("Error starting transaction.");
+    purge_account(&owner.username, &mut tx)
+        .await
+        .expect("Error purging account.");
+    tx.rollback()
+        .await
+        .expect("Error rolling back transaction.");
+    assert!(is_chat_member(&group.chat_id, &owner.username, &pool).await);
+    delete_account(&owner.username, &pool)
+        .await
+        .expect("Error deleting account.");
+    assert!(!is_chat_member(&group.chat_id, &owner.username, &pool).await);
+    let promoted: ChatMember = get_chat_member(&group.chat_id, &member.username, &pool)
+        .await
+        .expect("Error fetching member.");
+    assert_eq!(promoted.member_role, OWNER_ROLE);
+    remove_test_user(&member.username, &pool).await;
+}
+
+#[tokio::test]
+pub async fn test_audit_log(){
+    let pool: Pool<Postgres> = test_pool().await;
+    let actor: Option<String> = Some("auditactor".to_string());
+    let before: usize = get_audit_page(&actor, &None, &None, &MAX_PAGE_SIZE, &pool)
+        .await
+        .expect("Error fetching audit log.")
+        .len();
+    let mut tx: Transaction<'_, Postgres> = pool.begin()
+        .await
+        .expect("Error starting transaction.");
+    record_audit("auditactor", AUDIT_HOST_EDIT, &None, &None, &None, &mut tx)
+        .await
+        .expect("Error recording audit entry.");
+    tx.rollback()
+        .await
+        .expect("Error rolling back transaction.");
+    let entries: Vec<AuditEntry> = get_audit_page(&actor, &None, &None, &MAX_PAGE_SIZE, &pool)
+        .await
+        .expect("Error fetching audit log.");
+    assert_eq!(entries.len(), before);
+    let mut tx: Transaction<'_, Postgres> = pool.begin()
+        .await
+        .expect("Error starting transaction.");
+    record_audit("auditactor", AUDIT_HOST_EDIT, &None, &None, &None, &mut tx)
+        .await
+        .expect("Error recording audit entry.");
+    tx.commit()
+        .await
+        .expect("Error committing transaction.");
+    let entries: Vec<AuditEntry> = get_audit_page(&actor, &None, &None, &MAX_PAGE_SIZE, &pool)
+        .await
+        .expect("Error fetching audit log.");
+    assert_eq!(entries.len(), before + 1);
+    let older: Vec<AuditEntry> = get_audit_page(&actor, &None, &Some(entries[0].seq), &MAX_PAGE_SIZE, &pool)
+        .await
+        .expect("Error fetching audit log.");
+    assert_eq!(older.len(), before);
+    assert!(
+        sqlx::query("UPDATE audit_log SET actor = 'tampered' WHERE seq = $1")
+            .bind(entries[0].seq)
+            .execute(&pool)
+            .await
+            .is_err()
+    );
+    assert!(
+        sqlx::query("DELETE FROM audit_log WHERE seq = $1")
+            .bind(entries[0].seq)
+            .execute(&pool)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+pub async fn test_role_hierarchy(){
+    let pool: Pool<Postgres> = test_pool().await;
+    let admin: User = test_user("rankadmin", &pool).await;
+    let moderator: User = test_user("rankmoderator", &pool).await;
+    let member: User = test_user("rankmember", &pool).await;
+    let mut tx: Transaction<'_, Postgres> = pool.begin()
+        .await
+        .expect("Error starting transaction.");
+    set_user_role(&admin.username, ROLE_ADMIN, &mut tx)
+        .await
+        .expect("Error setting role.");
+    set_user_role(&moderator.username, ROLE_MODERATOR, &mut tx)
+        .await
+        .expect("Error setting role.");
+    tx.commit()
+        .await
+        .expect("Error committing transaction.");
+    let mut tx: Transaction<'_, Postgres> = pool.begin()
+        .await
+        .expect("Error starting transaction.");
+    assert!(suspend_user(&moderator.username, &admin.username, "Spam.", &None, &mut tx).await.is_err());
+    tx.rollback()
+        .await
+        .expect("Error rolling back transaction.");
+    let mut tx: Transaction<'_, Postgres> = pool.begin()
+        .await
+        .expect("Error starting transaction.");
+    assert!(suspend_user(&moderator.username, &moderator.username, "Spam.", &None, &mut tx).await.is_err());
+    tx.rollback()
+        .await
+        .expect("Error rolling back transaction.");
+    let mut tx: Transaction<'_, Postgres> = pool.begin()
+        .await
+        .expect("Error starting transaction.");
+    suspend_user(&moderator.username, &member.username, "Spam.", &None, &mut tx)
+        .await
+        .expect("Error suspending user.");
+    tx.commit()
+        .await
+        .expect("Error committing transaction.");
+    let suspended: User = get_user_by_id(&member.username, &pool)
+        .await
+        .expect("Error fetching user.");
+    assert!(is_suspended(&suspended));
+    let promoted: User = get_user_by_id(&moderator.username, &pool)
+        .await
+        .expect("Error fetching user.");
+    assert!(has_permission(&promoted, &Permission::SuspendUsers));
+    assert!(!has_permission(&promoted, &Permission::ViewUsers));
+    let promoted: User = get_user_by_id(&admin.username, &pool)
+        .await
+        .expect("Error fetching user.");
+    assert!(has_permission(&promoted, &Permission::ViewUsers));
+    for user in [member, moderator, admin] {
+        remove_test_user(&user.username, &pool).await;
+        assert!(get_user_by_id(&user.username, &pool).await.is_err());
+    }
+}
+
+#[tokio::test]
+pub async fn test_upload_session_cap(){
+    let pool: Pool<Postgres> = test_pool().await;
+    let owner: User = test_user("uploadcap", &pool).await;
+    let checksum: String = hash_bytes(b"chunk");
+    assert_eq!(checksum, hash_string("chunk"));
+    let mut sessions: Vec<String> = Vec::new();
+    for index in 0..MAX_OPEN_UPLOADS {
+        let session_id: String = hash_string(&format!("uploadcap{}", index));
+        create_upload_session(&session_id, &owner.username, "file.bin", "/nonexistent", &5, &checksum, &1024, &pool)
+            .await
+            .expect("Error creating upload session.");
+        sessions.push(session_id);
+    }
+    assert!(
+        create_upload_session(&hash_string("uploadcapextra"), &owner.username, "file.bin", "/nonexistent", &5, &checksum, &1024, &pool)
+            .await
+            .is_err()
+    );
+    for session_id in sessions {
+        delete_upload_session(&session_id, &pool)
+            .await
+            .expect("Error deleting upload session.");
+    }
+    remove_test_user(&owner.username, &pool).await;
+}
+
+#[tokio::test]
+pub async fn test_mark_read(){
+    let pool: Pool<Postgres> = test_pool().await;
+    let owner: User = test_user("readowner", &pool).await;
+    let member: User = test_user("readmember", &pool).await;
+    let group: Chat = create_group(&owner.username, "Receipts", std::slice::from_ref(&member.username), &pool)
+        .await
+        .expect("Error creating group.");
+    let published: String = rfc2822_after(&-60);
+    let mut sent: Vec<String> = Vec::new();
+    for _ in 0..3 {
+        let message: Message = create_message("Hi!", &owner.username, &group.chat_id, &None, &None, &pool)
+            .await
+            .expect("Error sending message.");
+        sqlx::query("UPDATE messages SET published = $1 WHERE msg_id = $2")
+            .bind(&published)
+            .bind(&message.msg_id)
+            .execute(&pool)
+            .await
+            .expect("Error updating message.");
+        sent.push(message.msg_id);
+    }
+    sent.sort();
+    let later: Message = create_message("Hi!", &owner.username, &group.chat_id, &None, &None, &pool)
+        .await
+        .expect("Error sending message.");
+    mark_read(&group.chat_id, &sent[1], &member, &pool)
+        .await
+        .expect("Error marking messages as read.");
+    for (index, msg_id) in sent.iter().enumerate() {
+        let key: MessageKey = get_message_key(msg_id, &member.username, &pool)
+            .await
+            .expect("Error fetching key.");
+        assert_eq!(key.read_at.is_some(), index <= 1);
+    }
+    let key: MessageKey = get_message_key(&later.msg_id, &member.username, &pool)
+        .await
+        .expect("Error fetching key.");
+    assert!(key.read_at.is_none());
+    remove_test_user(&owner.username, &pool).await;
+    remove_test_user(&member.username, &pool).await;
+}
+
+#[tokio::test]
+pub async fn test_chat_mute(){
+    let pool: Pool<Postgres> = test_pool().await;
+    let sender: User = test_user("mutesender", &pool).await;
+    let receiver: User = test_user("mutereceiver", &pool).await;
+    let chat: Chat = open_chat(&sender.username, &receiver.username, &pool)
+        .await
+        .expect("Error opening chat.");
+    let users: Vec<String> = vec![sender.username.clone(), receiver.username.clone()];
+    set_chat_muted(&chat.chat_id, &receiver.username, &Some(rfc2822_after(&3600)), &pool)
+        .await
+        .expect("Error muting chat.");
+    assert_eq!(
+        unmuted_users(&chat.chat_id, &users, &pool).await.expect("Error checking mutes."),
+        vec![sender.username.clone()]
+    );
+    set_chat_muted(&chat.chat_id, &receiver.username, &Some(rfc2822_after(&-60)), &pool)
+        .await
+        .expect("Error muting chat.");
+    assert_eq!(
+        unmuted_users(&chat.chat_id, &users, &pool).await.expect("Error checking mutes."),
+        users
+    );
+    let nickname: String = "a".repeat(MAX_NICKNAME_LENGTH + 1);
+    let error: YokaiErr = set_chat_nickname(&chat.chat_id, &receiver.username, &Some(nickname), &pool)
+        .await
+        .expect_err("Nickname should be rejected.");
+    assert!(error.to_string().contains(&MAX_NICKNAME_LENGTH.to_string()));
+    remove_test_user(&sender.username, &pool).await;
+    remove_test_user(&receiver.username, &pool).await;
+}
+
+#[tokio::test]
+pub async fn test_report_queue(){
+    let pool: Pool<Postgres> = test_pool().await;
+    let reporter: User = test_user("queuereporter", &pool).await;
+    let target: User = test_user("queuetarget", &pool).await;
+    let admin: User = test_user("queueadmin", &pool).await;
+    let first: Report = create_report(&reporter.username, &target.username, &None, "Spam.", &None, &pool)
+        .await
+        .expect("Error creating report.");
+    assert!(
+        create_report(&reporter.username, &target.username, &None, "Spam again.", &None, &pool)
+            .await
+            .is_err()
+    );
+    resolve_report(&first.report_id, &admin.username, ACTION_DISMISS, &None, &None, &pool)
+        .await
+        .expect("Error resolving report.");
+    let second: Report = create_report(&reporter.username, &target.username, &None, "Spam again.", &None, &pool)
+        .await
+        .expect("Error creating report.");
+    sqlx::query("UPDATE reports SET created = $1 WHERE report_id = $2")
+        .bind(rfc2822_after(&-60))
+        .bind(&first.report_id)
+        .execute(&pool)
+        .await
+        .expect("Error updating report.");
+    let total: i64 = count_reports(&None, &pool)
+        .await
+        .expect("Error counting reports.");
+    let queue: Vec<String> = get_reports(&None, &total, &0, &pool)
+        .await
+        .expect("Error fetching reports.")
+        .into_iter()
+        .map(|report| report.report_id)
+        .filter(|report_id| *report_id == first.report_id || *report_id == second.report_id)
+        .collect::<Vec<String>>();
+    assert_eq!(queue, vec![first.report_id.clone(), second.report_id.clone()]);
+    assert!(get_reports(&None, &1, &total, &pool).await.expect("Error fetching reports.").is_empty());
+    sqlx::query("DELETE FROM reports WHERE reporter = $1")
+        .bind(&reporter.username)
+        .execute(&pool)
+        .await
+        .expect("Error deleting reports.");
+    remove_test_user(&reporter.username, &pool).await;
+    remove_test_user(&target.username, &pool).await;
+    remove_test_user(&admin.username, &pool).await;
+}
+
+#[tokio::test]
+pub async fn test_message_fanout(){
+    let pool: Pool<Postgres> = test_pool().await;
+    let owner: User = test_user("fanoutowner", &pool).await;
+    let member: User = test_user("fanoutmember", &pool).await;
+    let group: Chat = create_group(&owner.username, "Fanout", &[member.username.clone(), owner.username.clone()], &pool)
+        .await
+        .expect("Error creating group.");
+    assert!(is_chat_member(&group.chat_id, &member.username, &pool).await);
+    sqlx::query("UPDATE users SET public_key = 'invalid' WHERE username = $1")
+        .bind(&member.username)
+        .execute(&pool)
+        .await
+        .expect("Error updating user.");
+    assert!(
+        create_message("Hi!", &owner.username, &group.chat_id, &None, &None, &pool)
+            .await
+            .is_err()
+    );
+    assert!(
+        get_chat_messages(&group.chat_id, &pool)
+            .await
+            .expect("Error fetching messages.")
+            .iter()
+            .all(|message| message.is_system)
+    );
+    remove_test_user(&owner.username, &pool).await;
+    remove_test_user(&member.username, &pool).await;
+}
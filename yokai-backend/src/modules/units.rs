@@ -6,19 +6,101 @@ Licensed under the FSL v1.
 
 use sqlx::Pool;
 use serde::Deserialize;
+use super::events::EventHub;
 use sqlx::postgres::Postgres;
+use super::presence::PresenceStore;
 use actix_multipart::form::MultipartForm;
 use actix_multipart::form::tempfile::TempFile;
 use actix_multipart::form::json::Json as MPJson;
 
+pub const UPLOAD_CHUNK_LIMIT: usize = 8388608;
+
+pub const MAX_OPEN_UPLOADS: i64 = 4;
+
+pub const MULTIPART_METADATA_LIMIT: usize = 65536;
+
+pub const DOWNLOAD_LINK_TTL: i64 = 300;
+
+pub const MAX_MESSAGE_BYTES: usize = 245;
+
+pub const REGISTRATION_MODE: &str = "invite";
+
+pub const ENCRYPTION_SCHEMES: [&str; 1] = ["rsa-2048-pkcs1"];
+
+pub const OWNER_ROLE: &str = "owner";
+
+pub const ADMIN_ROLE: &str = "admin";
+
+pub const MEMBER_ROLE: &str = "member";
+
+pub const MESSAGE_TTLS: [i64; 3] = [3600, 86400, 604800];
+
+pub const MAX_NICKNAME_LENGTH: usize = 64;
+
+pub const REPORT_OPEN: &str = "open";
+
+pub const REPORT_RESOLVED: &str = "resolved";
+
+pub const REPORT_DISMISSED: &str = "dismissed";
+
+pub const ACTION_DISMISS: &str = "dismiss";
+
+pub const ACTION_DELETE_MESSAGE: &str = "delete_message";
+
+pub const ACTION_KICK: &str = "kick";
+
+pub const ACTION_SUSPEND: &str = "suspend";
+
+pub const DEFAULT_PAGE_SIZE: i64 = 50;
+
+pub const MAX_PAGE_SIZE: i64 = 500;
+
+pub const AUDIT_INVITE_CREATE: &str = "invite.create";
+
+pub const AUDIT_HOST_EDIT: &str = "host.edit";
+
+pub const AUDIT_USER_KICK: &str = "user.kick";
+
+pub const AUDIT_USER_ROLE: &str = "user.role";
+
+pub const AUDIT_USER_SUSPEND: &str = "user.suspend";
+
+pub const AUDIT_USER_UNSUSPEND: &str = "user.unsuspend";
+
+pub const AUDIT_REPORT_RESOLVE: &str = "report.resolve";
+
+pub const MAX_INSTANCE_NAME_LENGTH: usize = 64;
+
+pub const MAX_DESCRIPTION_LENGTH: usize = 1024;
+
+pub const MAX_POLICY_LENGTH: usize = 65536;
+
+pub const MAX_CONTACT_ADDRESS_LENGTH: usize = 254;
+
+pub const BRANDING_LOGO: &str = "logo";
+
+pub const BRANDING_FAVICON: &str = "favicon";
+
 
 pub struct KeyPair {
     pub private_key: String,
     pub public_key: String
 }
 
+pub struct RenderedVariant {
+    pub variant: String,
+    pub file_path: String
+}
+
 pub struct AppData {
-    pub pool: Pool<Postgres>
+    pub pool: Pool<Postgres>,
+    pub max_upload_size: i64,
+    pub signing_key: String,
+    pub clamav_socket: Option<String>,
+    pub edit_window: i64,
+    pub trusted_proxies: Vec<String>,
+    pub events: EventHub,
+    pub presence: PresenceStore
 }
 
 pub struct Config{
@@ -36,6 +118,11 @@ pub struct Config{
     pub admin_primary_color: String,
     pub admin_tertiary_color: String,
     pub admin_secondary_color: String,
+    pub max_upload_size: i64,
+    pub signing_key: String,
+    pub clamav_socket: Option<String>,
+    pub edit_window: i64,
+    pub trusted_proxies: Vec<String>
 }
 
 #[derive(Debug, Deserialize)]
@@ -46,7 +133,30 @@ pub struct FileMetadata {
 
 #[derive(Debug, MultipartForm)]
 pub struct FileUploadForm {
-    #[multipart(limit = "30MB")]
     pub file: TempFile,
     pub json: MPJson<FileMetadata>,
 }
+
+#[derive(Debug, Deserialize)]
+pub struct BrandingMetadata {
+    pub api_token: String,
+    pub instance_name: Option<String>,
+    pub description: Option<String>,
+    pub primary_color: Option<String>,
+    pub secondary_color: Option<String>,
+    pub tertiary_color: Option<String>,
+    pub terms: Option<String>,
+    pub privacy_policy: Option<String>,
+    pub contact_address: Option<String>,
+    pub remove_logo: Option<bool>,
+    pub remove_favicon: Option<bool>
+}
+
+#[derive(Debug, MultipartForm)]
+pub struct BrandingForm {
+    #[multipart(limit = "5MB")]
+    pub logo: Option<TempFile>,
+    #[multipart(limit = "1MB")]
+    pub favicon: Option<TempFile>,
+    pub json: MPJson<BrandingMetadata>,
+}
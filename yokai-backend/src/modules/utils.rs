@@ -7,19 +7,34 @@ use sqlx::Pool;
 use chrono::Utc;
 use sha2::Digest;
 use sha2::Sha256;
+use std::fs::File;
+use std::io::Read;
 use base64::Engine;
 use sqlx::postgres;
+use openssl::memcmp;
+use std::path::Path;
+use chrono::Duration;
 use chrono::DateTime;
 use openssl::rsa::Rsa;
+use std::path::PathBuf;
+use super::models::User;
 use openssl::pkey::PKey;
 use super::err::YokaiErr;
+use openssl::sign::Signer;
+use std::env::current_dir;
 use openssl::pkey::Public;
 use super::units::KeyPair;
 use openssl::rsa::Padding;
+use actix_web::HttpRequest;
 use openssl::pkey::Private;
+use std::fs::create_dir_all;
 use sqlx::postgres::Postgres;
+use openssl::rand::rand_bytes;
 use openssl::encrypt::Encrypter;
+use openssl::hash::MessageDigest;
 use base64::engine::general_purpose;
+use super::units::MAX_MESSAGE_BYTES;
+use super::units::MAX_CONTACT_ADDRESS_LENGTH;
 
 pub fn hash_string(subject: &str) -> String {
     let mut hasher: Sha256 = Sha256::new();
@@ -27,11 +42,24 @@ pub fn hash_string(subject: &str) -> String {
     format!("{:X}", hasher.finalize())
 }
 
+pub fn hash_bytes(subject: &[u8]) -> String {
+    let mut hasher: Sha256 = Sha256::new();
+    hasher.update(subject);
+    format!("{:X}", hasher.finalize())
+}
+
 pub fn rfc2282() -> String {
     let now: DateTime<Utc> = Utc::now();
     now.to_rfc2822()
 }
 
+pub fn rfc2822_after(
+    seconds: &i64
+) -> String {
+    let then: DateTime<Utc> = Utc::now() + Duration::seconds(*seconds);
+    then.to_rfc2822()
+}
+
 pub fn generate_keypair(
 ) -> Result<KeyPair, YokaiErr>{
     let keys: Rsa<Private> = match Rsa::generate(2048){
@@ -145,7 +173,7 @@ pub fn encrypt_message(
     };
     let mut buffer: Vec<u8> = vec![0;buf_len];
     let encryption: usize = match encrypter.encrypt(
-        &msg.to_string().as_bytes(), 
+        msg.to_string().as_bytes(), 
         &mut buffer
     ){
         Ok(encryption) => encryption,
@@ -242,15 +270,91 @@ pub fn check_color_str(
 pub fn check_message(
     subject: &str
 ) -> bool {
-    let msg_chars: Vec<char> = subject
-        .to_string()
+    subject.len() <= MAX_MESSAGE_BYTES
+}
+
+pub fn is_emoji_char(
+    subject: &char
+) -> bool {
+    matches!(
+        *subject as u32,
+        0x00A9 | 0x00AE | 0x203C | 0x2049 | 0x2122 | 0x2139 |
+        0x2194..=0x2199 | 0x21A9..=0x21AA | 0x231A..=0x231B |
+        0x2328 | 0x23CF | 0x23E9..=0x23F3 | 0x23F8..=0x23FA |
+        0x24C2 | 0x25AA..=0x25AB | 0x25B6 | 0x25C0 |
+        0x25FB..=0x25FE | 0x2600..=0x27BF | 0x2934..=0x2935 |
+        0x2B05..=0x2B07 | 0x2B1B..=0x2B1C | 0x2B50 | 0x2B55 |
+        0x3030 | 0x303D | 0x3297 | 0x3299 | 0x1F000..=0x1FAFF
+    )
+}
+
+pub fn is_emoji_component(
+    subject: &char
+) -> bool {
+    matches!(
+        *subject as u32,
+        0x200D | 0x20E3 | 0xFE0E | 0xFE0F | 0xE0020..=0xE007F
+    )
+}
+
+pub fn check_emoji(
+    subject: &str
+) -> bool {
+    let emoji_chars: Vec<char> = subject
         .chars()
         .collect::<Vec<char>>();
-    msg_chars.len() <= 245
+    let keycap: bool = emoji_chars.contains(&'\u{20E3}');
+    !emoji_chars.is_empty() &&
+    emoji_chars.len() <= 8 &&
+    (emoji_chars.iter().any(is_emoji_char) || keycap) &&
+    emoji_chars.iter().all(
+        |c| is_emoji_char(c) ||
+            is_emoji_component(c) ||
+            (keycap && (c.is_ascii_digit() || *c == '#' || *c == '*'))
+    )
+}
+
+pub fn check_contact_address(
+    subject: &str
+) -> bool {
+    let parts: Vec<&str> = subject
+        .split('@')
+        .collect::<Vec<&str>>();
+    parts.len() == 2 &&
+    subject.len() <= MAX_CONTACT_ADDRESS_LENGTH &&
+    !parts[0].is_empty() &&
+    parts[1].contains('.') &&
+    !parts[1].starts_with('.') &&
+    !parts[1].ends_with('.') &&
+    !subject.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+pub fn edit_optional_text(
+    current: &Option<String>,
+    new_value: &Option<String>,
+    max_length: usize
+) -> Result<Option<String>, YokaiErr> {
+    match new_value {
+        None => Ok(current.clone()),
+        Some(value) => {
+            let trimmed: &str = value.trim();
+            if trimmed.is_empty(){
+                Ok(None)
+            }
+            else if trimmed.chars().count() > max_length {
+                Err::<Option<String>, YokaiErr>(
+                    YokaiErr::new("Supplied text exceeds the maximum length.")
+                )
+            }
+            else {
+                Ok(Some(trimmed.to_string()))
+            }
+        }
+    }
 }
 
 pub async fn create_connection(
-    db_url: &String
+    db_url: &str
 ) -> Result<Pool<Postgres>, YokaiErr> {
     let conn = match postgres::PgPool::connect(
         db_url
@@ -262,3 +366,203 @@ pub async fn create_connection(
     };
     Ok(conn)
 }
+
+pub fn storage_dir(
+    name: &str
+) -> Result<PathBuf, YokaiErr> {
+    let mut path_buf: PathBuf = match current_dir(){
+        Ok(current) => current,
+        Err(e) => return Err::<PathBuf, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    path_buf.push(name);
+    if !path_buf.exists(){
+        let _created_dir: () = match create_dir_all(
+            path_buf.as_path()
+        ){
+            Ok(_created_dir) => {},
+            Err(e) => return Err::<PathBuf, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+    }
+    Ok(path_buf)
+}
+
+pub fn hash_file(
+    path: &Path
+) -> Result<String, YokaiErr> {
+    let mut file: File = match File::open(path){
+        Ok(file) => file,
+        Err(e) => return Err::<String, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut hasher: Sha256 = Sha256::new();
+    let mut buffer: Vec<u8> = vec![0;65536];
+    loop {
+        let read: usize = match file.read(&mut buffer){
+            Ok(read) => read,
+            Err(e) => return Err::<String, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        };
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:X}", hasher.finalize()))
+}
+
+pub fn seconds_since(
+    timestamp: &str
+) -> Result<i64, YokaiErr> {
+    let then: DateTime<Utc> = match DateTime::parse_from_rfc2822(timestamp){
+        Ok(then) => then.with_timezone(&Utc),
+        Err(e) => return Err::<i64, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok((Utc::now() - then).num_seconds())
+}
+
+pub fn is_suspended(
+    user: &User
+) -> bool {
+    if user.suspended_at.is_none(){
+        return false;
+    }
+    match &user.suspended_until {
+        Some(until) => seconds_since(until).is_ok_and(|since| since < 0),
+        None => true
+    }
+}
+
+pub fn file_size(
+    path: &str
+) -> u64 {
+    match std::fs::metadata(path){
+        Ok(metadata) => metadata.len(),
+        Err(_e) => 0
+    }
+}
+
+pub fn like_pattern(
+    term: &str
+) -> String {
+    let escaped: String = term
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+pub fn request_ip(
+    req: &HttpRequest,
+    trusted_proxies: &[String]
+) -> Option<String> {
+    let mut client: String = match req.peer_addr(){
+        Some(addr) => addr.ip().to_string(),
+        None => return None
+    };
+    let forwarded: &str = match req.headers().get("X-Forwarded-For"){
+        Some(forwarded) => forwarded.to_str().unwrap_or(""),
+        None => ""
+    };
+    for hop in forwarded.rsplit(',') {
+        if !trusted_proxies.contains(&client) {
+            break;
+        }
+        let hop: &str = hop.trim();
+        if hop.is_empty() {
+            break;
+        }
+        client = hop.to_string();
+    }
+    Some(client)
+}
+
+pub fn get_header(
+    req: &HttpRequest,
+    name: &str
+) -> Result<String, YokaiErr> {
+    let value: &str = match req.headers().get(name){
+        Some(value) => match value.to_str(){
+            Ok(value) => value,
+            Err(e) => return Err::<String, YokaiErr>(
+                YokaiErr::new(&e.to_string())
+            )
+        },
+        None => return Err::<String, YokaiErr>(
+            YokaiErr::new(&format!("Missing header \"{}\".", name))
+        )
+    };
+    Ok(value.to_string())
+}
+
+pub fn generate_secret() -> Result<String, YokaiErr> {
+    let mut buffer: Vec<u8> = vec![0;32];
+    let _: () = match rand_bytes(&mut buffer){
+        Ok(_f) => {},
+        Err(e) => return Err::<String, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(general_purpose::URL_SAFE_NO_PAD.encode(&buffer))
+}
+
+pub fn sign_download(
+    file_id: &str,
+    expires: &i64,
+    key: &str
+) -> Result<String, YokaiErr> {
+    let hmac_key: PKey<Private> = match PKey::hmac(key.as_bytes()){
+        Ok(hmac_key) => hmac_key,
+        Err(e) => return Err::<String, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let mut signer: Signer = match Signer::new(
+        MessageDigest::sha256(),
+        &hmac_key
+    ){
+        Ok(signer) => signer,
+        Err(e) => return Err::<String, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let _: () = match signer.update(
+        format!("{}:{}", file_id, expires).as_bytes()
+    ){
+        Ok(_f) => {},
+        Err(e) => return Err::<String, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    let signature: Vec<u8> = match signer.sign_to_vec(){
+        Ok(signature) => signature,
+        Err(e) => return Err::<String, YokaiErr>(
+            YokaiErr::new(&e.to_string())
+        )
+    };
+    Ok(general_purpose::URL_SAFE_NO_PAD.encode(&signature))
+}
+
+pub fn verify_download(
+    file_id: &str,
+    expires: &i64,
+    signature: &str,
+    key: &str
+) -> bool {
+    if *expires < Utc::now().timestamp() {
+        return false;
+    }
+    let expected: String = match sign_download(file_id, expires, key){
+        Ok(expected) => expected,
+        Err(_e) => return false
+    };
+    expected.len() == signature.len() &&
+        memcmp::eq(expected.as_bytes(), signature.as_bytes())
+}